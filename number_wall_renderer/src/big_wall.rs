@@ -0,0 +1,363 @@
+use num_bigint::BigInt;
+use num_traits::{One, Zero, Signed};
+
+use crate::Boundary;
+
+// The arithmetic that a wall is computed in
+pub trait WallArithmetic {
+    type Value: Clone;
+
+    fn zero(&self) -> Self::Value;
+    fn one(&self) -> Self::Value;
+    fn is_zero(&self, value: &Self::Value) -> bool;
+    fn add(&self, a: &Self::Value, b: &Self::Value) -> Self::Value;
+    fn sub(&self, a: &Self::Value, b: &Self::Value) -> Self::Value;
+    fn mul(&self, a: &Self::Value, b: &Self::Value) -> Self::Value;
+    fn div(&self, num: &Self::Value, den: &Self::Value) -> Result<Self::Value, String>;
+
+    fn neg(&self, value: &Self::Value) -> Self::Value {
+        self.sub(&self.zero(), value)
+    }
+
+    fn product(&self, values: &[&Self::Value]) -> Self::Value {
+        values.iter().fold(self.one(), |p, v| self.mul(&p, v))
+    }
+
+    // The value of a term of the sequence in the wall
+    fn term(&self, value: Self::Value) -> Result<Self::Value, String> {
+        Ok(value)
+    }
+}
+
+// Exact arithmetic for big integer walls, over the integers when the modulo is 0, otherwise mod the modulo
+pub struct BigArithmetic {
+    modulo: Option<BigInt>
+}
+
+impl BigArithmetic {
+    pub fn new(modulo: BigInt) -> Self {
+        BigArithmetic { modulo: if modulo.is_zero() { None } else { Some(modulo) } }
+    }
+
+    fn reduce(&self, value: BigInt) -> BigInt {
+        match &self.modulo {
+            Some(m) => ((value % m) + m) % m,
+            None => value
+        }
+    }
+}
+
+impl WallArithmetic for BigArithmetic {
+    type Value = BigInt;
+
+    fn zero(&self) -> BigInt {
+        BigInt::zero()
+    }
+
+    fn one(&self) -> BigInt {
+        BigInt::one()
+    }
+
+    fn is_zero(&self, value: &BigInt) -> bool {
+        value.is_zero()
+    }
+
+    fn add(&self, a: &BigInt, b: &BigInt) -> BigInt {
+        self.reduce(a + b)
+    }
+
+    fn sub(&self, a: &BigInt, b: &BigInt) -> BigInt {
+        self.reduce(a - b)
+    }
+
+    fn mul(&self, a: &BigInt, b: &BigInt) -> BigInt {
+        self.reduce(a * b)
+    }
+
+    fn div(&self, num: &BigInt, den: &BigInt) -> Result<BigInt, String> {
+        match &self.modulo {
+            None => {
+                if !(num % den).is_zero() {
+                    return Err(format!("{num} is not divisible by {den}, the wall is not an integer wall"));
+                }
+                Ok(num / den)
+            },
+            Some(m) => {
+                let inverse = mod_inverse(den, m).ok_or(format!("{den} has no inverse mod {m}, use composite for a modulo that is not prime"))?;
+                Ok(self.reduce(self.reduce(num.clone()) * inverse))
+            }
+        }
+    }
+
+    fn term(&self, value: BigInt) -> Result<BigInt, String> {
+        Ok(self.reduce(value))
+    }
+}
+
+pub fn mod_inverse(value: &BigInt, modulo: &BigInt) -> Option<BigInt> {
+    let (mut old_r, mut r) = (((value % modulo) + modulo) % modulo, modulo.clone());
+    let (mut old_s, mut s) = (BigInt::one(), BigInt::zero());
+    while !r.is_zero() {
+        let q = &old_r / &r;
+        (old_r, r) = (r.clone(), old_r - &q * &r);
+        (old_s, s) = (s.clone(), old_s - &q * &s);
+    }
+    if old_r.abs().is_one() {
+        Some(((old_s % modulo) + modulo) % modulo)
+    } else {
+        None
+    }
+}
+
+// One row of the wall, `None` cells could not be computed from the available terms
+struct Row<V> {
+    start: isize,
+    cells: Vec<Option<V>>
+}
+
+impl<V> Row<V> {
+    fn get(&self, column: isize) -> Option<&V> {
+        if column < self.start {
+            return None;
+        }
+        self.cells.get((column-self.start) as usize)?.as_ref()
+    }
+
+    fn end(&self) -> isize {
+        self.start + self.cells.len() as isize - 1
+    }
+}
+
+// A square of zeros, `top` and `left` are its first row and column.
+// A window that is `cut_off` runs past the known cells at its side, so it is at least `size` wide.
+struct Window {
+    top: usize,
+    left: isize,
+    size: usize,
+    cut_off: bool
+}
+
+// The window that the zero at `row`, `column` is part of, `None` if its top is not known
+fn find_window<A: WallArithmetic>(rows: &[Row<A::Value>], arithmetic: &A, row: usize, column: isize) -> Option<Window> {
+    let is_zero = |r: usize, c: isize| rows[r].get(c).map(|v| arithmetic.is_zero(v));
+
+    let mut top = row;
+    while is_zero(top-1, column)? {
+        top -= 1;
+    }
+    let mut cut_off = false;
+    let mut left = column;
+    loop {
+        match is_zero(top, left-1) {
+            Some(true) => { left -= 1; },
+            Some(false) => break,
+            None => { cut_off = true; break; }
+        }
+    }
+    let mut right = column;
+    loop {
+        match is_zero(top, right+1) {
+            Some(true) => { right += 1; },
+            Some(false) => break,
+            None => { cut_off = true; break; }
+        }
+    }
+    Some(Window { top, left, size: (right-left+1) as usize, cut_off })
+}
+
+// The cells around a window, named as in the frame theorems.
+// The inner frame is A along the top, B down the left, C down the right and D along the bottom, each indexed from 0 to size+1,
+// the outer frame is E, F, G and H just outside of them.
+struct Frame<'a, V> {
+    rows: &'a [Row<V>],
+    window: &'a Window
+}
+
+impl<'a, V> Frame<'a, V> {
+    fn cell(&self, row: usize, column: isize) -> Option<&'a V> {
+        self.rows.get(row)?.get(column)
+    }
+    fn a(&self, k: usize) -> Option<&'a V> { self.cell(self.window.top-1, self.window.left-1+k as isize) }
+    fn b(&self, k: usize) -> Option<&'a V> { self.cell(self.window.top-1+k, self.window.left-1) }
+    fn c(&self, k: usize) -> Option<&'a V> { self.cell(self.window.top-1+k, self.window.left+self.window.size as isize) }
+    fn d(&self, k: usize) -> Option<&'a V> { self.cell(self.window.top+self.window.size, self.window.left-1+k as isize) }
+    fn e(&self, k: usize) -> Option<&'a V> { self.cell(self.window.top-2, self.window.left-1+k as isize) }
+    fn f(&self, k: usize) -> Option<&'a V> { self.cell(self.window.top-1+k, self.window.left-2) }
+    fn g(&self, k: usize) -> Option<&'a V> { self.cell(self.window.top-1+k, self.window.left+self.window.size as isize+1) }
+}
+
+fn sign<A: WallArithmetic>(negative: bool, value: A::Value, arithmetic: &A) -> A::Value {
+    if negative { arithmetic.neg(&value) } else { value }
+}
+
+// The bottom of the inner frame is geometric, and its ratio S satisfies PR/QS = (-1)^g,
+// so D_k = (-1)^gk D_0 A_k C_k / (C_0 B_k)
+fn inner_frame_bottom<A: WallArithmetic>(frame: &Frame<A::Value>, d_0: &A::Value, k: usize, arithmetic: &A) -> Result<Option<A::Value>, String> {
+    let (Some(a_k), Some(b_k), Some(c_0), Some(c_k)) = (frame.a(k), frame.b(k), frame.c(0), frame.c(k)) else {
+        return Ok(None);
+    };
+    let num = sign(frame.window.size*k % 2 == 1, arithmetic.product(&[d_0, a_k, c_k]), arithmetic);
+    arithmetic.div(&num, &arithmetic.mul(c_0, b_k)).map(Some)
+}
+
+// The outer frame below the window, from QE_k/A_k + (-1)^k PF_k/B_k = H_j/(R D_j) + (-1)^k G_j/(S C_j) with j = g+1-k
+fn outer_frame_bottom<A: WallArithmetic>(frame: &Frame<A::Value>, j: usize, arithmetic: &A) -> Result<Option<A::Value>, String> {
+    let k = frame.window.size + 1 - j;
+    let cells = [frame.a(0), frame.a(1), frame.a(k), frame.b(0), frame.b(1), frame.b(k), frame.c(0), frame.c(1), frame.c(j),
+        frame.d(0), frame.d(1), frame.d(j), frame.e(k), frame.f(k), frame.g(j)];
+    if cells.iter().any(|c| c.is_none()) {
+        return Ok(None);
+    }
+    let [a_0, a_1, a_k, b_0, b_1, b_k, c_0, c_1, c_j, d_0, d_1, d_j, e_k, f_k, g_j] = cells.map(|c| c.unwrap());
+
+    // every term is brought over the denominator B_0 A_k A_0 B_k D_1 C_j
+    let top = arithmetic.product(&[b_1, e_k, a_0, b_k, d_1, c_j]);
+    let left = arithmetic.product(&[a_1, f_k, b_0, a_k, d_1, c_j]);
+    let right = arithmetic.product(&[d_0, g_j, b_0, a_k, a_0, b_k]);
+    let sides = sign(k % 2 == 1, arithmetic.sub(&left, &right), arithmetic);
+    let num = arithmetic.product(&[c_1, d_j, &arithmetic.add(&top, &sides)]);
+    let den = arithmetic.product(&[c_0, b_0, a_k, a_0, b_k, d_1, c_j]);
+    arithmetic.div(&num, &den).map(Some)
+}
+
+fn next_row<A: WallArithmetic>(rows: &[Row<A::Value>], arithmetic: &A) -> Result<Row<A::Value>, String> {
+    let t = rows.len();
+    let (above, centre) = (&rows[t-2], &rows[t-1]);
+    let start = centre.start + 1;
+    let mut cells: Vec<Option<A::Value>> = Vec::with_capacity(centre.cells.len().saturating_sub(2));
+    // cells on the bottom of an inner frame need the corner to their left, so they are filled in after the rest of the row
+    let mut inner_bottom: Vec<(isize, Window)> = vec![];
+
+    for column in start..centre.end() {
+        let cell = match (above.get(column), centre.get(column)) {
+            (Some(n), Some(x)) if !arithmetic.is_zero(n) => {
+                match (centre.get(column-1), centre.get(column+1)) {
+                    (Some(w), Some(e)) => {
+                        let num = arithmetic.sub(&arithmetic.mul(x, x), &arithmetic.mul(w, e));
+                        Some(arithmetic.div(&num, n).map_err(|e| format!("row {t}, column {column}: {e}"))?)
+                    },
+                    _ => None
+                }
+            },
+            (Some(_), Some(x)) if arithmetic.is_zero(x) => {
+                match find_window(rows, arithmetic, t-1, column) {
+                    Some(window) if window.top + window.size > t => Some(arithmetic.zero()),
+                    Some(window) if !window.cut_off => {
+                        inner_bottom.push((column, window));
+                        None
+                    },
+                    _ => None
+                }
+            },
+            (Some(_), Some(_)) => {
+                match find_window(rows, arithmetic, t-2, column) {
+                    Some(window) if !window.cut_off => {
+                        let j = (column - window.left + 1) as usize;
+                        outer_frame_bottom(&Frame { rows, window: &window }, j, arithmetic)?
+                    },
+                    _ => None
+                }
+            },
+            _ => None
+        };
+        cells.push(cell);
+    }
+
+    let mut row = Row { start, cells };
+    for (column, window) in inner_bottom {
+        let Some(d_0) = row.get(window.left-1).cloned() else {
+            continue;
+        };
+        let k = (column - window.left + 1) as usize;
+        let value = inner_frame_bottom(&Frame { rows, window: &window }, &d_0, k, arithmetic)?;
+        row.cells[(column-start) as usize] = value;
+    }
+    Ok(row)
+}
+
+// The rows of the wall inside `boundary`, `sequence` gives `None` for terms that are not known.
+// The rows are computed from a wider stretch of the sequence than the boundary,
+// so the frames of windows that reach outside of it are still known.
+pub fn big_wall_lines<A, F>(sequence: F, arithmetic: &A, boundary: &Boundary) -> Result<Vec<Vec<Option<A::Value>>>, String>
+    where
+        A: WallArithmetic,
+        F: Fn(isize) -> Option<A::Value>
+{
+    let margin = 2*boundary.bottom as isize + 2;
+    let (start, end) = (boundary.left - margin, boundary.right + margin);
+    let width = (end-start+1) as usize;
+
+    let terms = (start..=end)
+        .map(|n| sequence(n).map(|v| arithmetic.term(v).map_err(|e| format!("term {n}: {e}"))).transpose())
+        .collect::<Result<_, _>>()?;
+    let mut rows = vec![
+        Row { start, cells: vec![Some(arithmetic.zero()); width] },
+        Row { start, cells: vec![Some(arithmetic.one()); width] },
+        Row { start, cells: terms }
+    ];
+    while rows.len() <= boundary.bottom {
+        let row = next_row(&rows, arithmetic)?;
+        rows.push(row);
+    }
+
+    Ok(rows[boundary.top..=boundary.bottom].iter()
+        .map(|row| (boundary.left..=boundary.right).map(|c| row.get(c).cloned()).collect())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::determinant;
+    use crate::random_sequence::RandomSequence;
+
+    // Checks every cell of the wall against its Toeplitz determinant
+    fn check_against_determinants(arithmetic: &BigArithmetic, terms: impl Fn(isize) -> Option<BigInt>, boundary: &Boundary) {
+        let lines = big_wall_lines(&terms, arithmetic, boundary).unwrap();
+        for (y, line) in lines.iter().enumerate() {
+            let row = boundary.top + y;
+            for (x, cell) in line.iter().enumerate() {
+                let column = boundary.left + x as isize;
+                let expected = if row == 0 { Some(BigInt::zero()) } else { determinant::toeplitz(arithmetic, &terms, column, row - 1).unwrap() };
+                assert_eq!(cell, &expected, "row {row}, column {column}");
+            }
+        }
+    }
+
+    #[test]
+    fn integer_walls_match_their_determinants() {
+        let boundary = Boundary { top: 0, bottom: 9, left: -6, right: 6 };
+        for seed in 0..4 {
+            let random = RandomSequence::new(seed, 7, 0.6);
+            check_against_determinants(&BigArithmetic::new(BigInt::zero()), |n| Some((random.value(n) - 3).into()), &boundary);
+        }
+    }
+
+    #[test]
+    fn walls_mod_a_prime_match_their_determinants() {
+        // most terms are zero, so there are windows of every size and the frame theorems are needed
+        let boundary = Boundary { top: 0, bottom: 16, left: -10, right: 10 };
+        for (seed, prime) in [(1, 2), (2, 2), (3, 3), (4, 5)] {
+            let random = RandomSequence::new(seed, prime, 0.4);
+            check_against_determinants(&BigArithmetic::new(prime.into()), |n| Some(random.value(n).into()), &boundary);
+        }
+    }
+
+    #[test]
+    fn finite_sequences_leave_the_cells_they_do_not_reach_undefined() {
+        // without zero windows, as the frame of a window at the edge is not known
+        let boundary = Boundary { top: 0, bottom: 5, left: 0, right: 7 };
+        let values = [1, 3, 4, 2, 2, 5, 1, 4];
+        check_against_determinants(&BigArithmetic::new(BigInt::zero()), |n| usize::try_from(n).ok().and_then(|i| values.get(i)).map(|&v| v.into()), &boundary);
+    }
+
+    #[test]
+    fn inverses() {
+        let m = BigInt::from(26);
+        assert_eq!(mod_inverse(&BigInt::from(3), &m), Some(BigInt::from(9)));
+        assert_eq!(mod_inverse(&BigInt::from(-3), &m), Some(BigInt::from(17)));
+        assert_eq!(mod_inverse(&BigInt::from(13), &m), None);
+        let arithmetic = BigArithmetic::new(BigInt::zero());
+        assert!(arithmetic.div(&BigInt::from(7), &BigInt::from(2)).is_err());
+    }
+}
@@ -0,0 +1,78 @@
+use std::rc::Rc;
+
+use crate::sequence_fn::{term_operation, SequenceFn, TermOperation};
+
+#[derive(Debug, Clone, Copy)]
+pub enum Combination {
+    // a(n) + b(n)
+    Add,
+    // a(n) * b(n)
+    Multiply,
+    // c1*a(n) + c2*b(n)
+    Linear(i128, i128)
+}
+
+// The combined sequence is only defined where both `a` and `b` are
+pub fn combine(a: SequenceFn, b: SequenceFn, combination: Combination, modulo: i128) -> SequenceFn {
+    let first = match (a.first, b.first) {
+        (Some(x), Some(y)) => Some(x.max(y)),
+        (x, y) => x.or(y)
+    };
+    let last = match (a.last, b.last) {
+        (Some(x), Some(y)) => Some(x.min(y)),
+        (x, y) => x.or(y)
+    };
+
+    let (a, b) = (a.func, b.func);
+    SequenceFn {
+        func: Rc::new(move |n| {
+            let term = || format!("the combined term at {n}");
+            match combination {
+                Combination::Add => term_operation(a(n), b(n), TermOperation::Add, modulo, term),
+                Combination::Multiply => term_operation(a(n), b(n), TermOperation::Mul, modulo, term),
+                Combination::Linear(c1, c2) => {
+                    let (x, y) = (term_operation(c1, a(n), TermOperation::Mul, modulo, term), term_operation(c2, b(n), TermOperation::Mul, modulo, term));
+                    term_operation(x, y, TermOperation::Add, modulo, term)
+                }
+            }
+        }),
+        first,
+        last
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sequence_fn::check_terms;
+
+    #[test]
+    fn combined_terms() {
+        let a = || SequenceFn::one_sided(|n| n as i128);
+        let b = || SequenceFn::two_sided(|n| n as i128 * 10 + 1);
+        let terms = |s: SequenceFn| (0..4).map(|n| s.value(n)).collect::<Vec<i128>>();
+        assert_eq!(terms(combine(a(), b(), Combination::Add, 0)), vec![1, 12, 23, 34]);
+        assert_eq!(terms(combine(a(), b(), Combination::Multiply, 0)), vec![0, 11, 42, 93]);
+        assert_eq!(terms(combine(a(), b(), Combination::Linear(2, -1), 0)), vec![-1, -9, -17, -25]);
+        assert_eq!(terms(combine(a(), b(), Combination::Linear(2, -1), 5)), vec![4, 1, 3, 0]);
+        check_terms().unwrap();
+    }
+
+    #[test]
+    fn domain_is_where_both_are_defined() {
+        let combined = combine(SequenceFn::finite(vec![1, 2, 3, 4], -1), SequenceFn::finite(vec![5, 6, 7], 1), Combination::Add, 0);
+        assert_eq!((combined.first, combined.last, combined.values()), (Some(1), Some(2), Some(vec![8, 10])));
+        let combined = combine(SequenceFn::one_sided(|_| 1), SequenceFn::two_sided(|_| 1), Combination::Add, 0);
+        assert_eq!((combined.first, combined.last), (Some(0), None));
+    }
+
+    #[test]
+    fn overflow_is_reported_without_a_modulo() {
+        let big = || SequenceFn::one_sided(|_| i128::MAX);
+        combine(big(), big(), Combination::Multiply, 0).value(0);
+        assert!(check_terms().is_err());
+        // i128::MAX = 1 mod 3
+        assert_eq!(combine(big(), big(), Combination::Linear(2, 2), 3).value(0), 1);
+        check_terms().unwrap();
+    }
+}
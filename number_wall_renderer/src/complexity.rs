@@ -0,0 +1,232 @@
+use std::fs;
+
+use crate::big_wall::WallArithmetic;
+
+pub struct ComplexitySettings {
+    // the profile is printed if there is no output file
+    pub output_file: Option<String>,
+    pub svg_file: Option<String>
+}
+
+// The linear complexity of every prefix of a sequence and the shortest linear recurrence of the whole sequence,
+// whose connection polynomial c has c_0 = 1 and s(n) + c_1 s(n-1) + ... + c_L s(n-L) = 0
+pub struct LinearComplexity<V> {
+    pub profile: Vec<usize>,
+    pub connection: Vec<V>
+}
+
+// The Berlekamp-Massey algorithm over a field, `profile[n]` is the linear complexity of the first n+1 terms
+pub fn berlekamp_massey<A: WallArithmetic>(arithmetic: &A, terms: &[A::Value]) -> Result<LinearComplexity<A::Value>, String> {
+    let mut connection = vec![arithmetic.one()];
+    let mut previous = vec![arithmetic.one()];
+    let mut previous_discrepancy = arithmetic.one();
+    let (mut length, mut shift) = (0, 1);
+    let mut profile = Vec::with_capacity(terms.len());
+
+    for n in 0..terms.len() {
+        let discrepancy = (1..=length).fold(terms[n].clone(), |d, i| {
+            arithmetic.add(&d, &arithmetic.mul(&connection[i], &terms[n-i]))
+        });
+        if arithmetic.is_zero(&discrepancy) {
+            shift += 1;
+        } else {
+            // connection -= (discrepancy / previous_discrepancy) x^shift previous
+            let factor = arithmetic.div(&discrepancy, &previous_discrepancy)?;
+            let mut next = connection.clone();
+            next.resize(next.len().max(previous.len() + shift), arithmetic.zero());
+            for (i, p) in previous.iter().enumerate() {
+                next[i + shift] = arithmetic.sub(&next[i + shift], &arithmetic.mul(&factor, p));
+            }
+            if 2*length <= n {
+                previous = std::mem::replace(&mut connection, next);
+                previous_discrepancy = discrepancy;
+                length = n + 1 - length;
+                shift = 1;
+            } else {
+                connection = next;
+                shift += 1;
+            }
+        }
+        profile.push(length);
+    }
+    connection.resize(length + 1, arithmetic.zero());
+    Ok(LinearComplexity { profile, connection })
+}
+
+// A linear recurrence s(n) = a_1 s(n-1) + ... + a_L s(n-L)
+pub struct Recurrence<V> {
+    pub coefficients: Vec<V>
+}
+
+impl<V: Clone> Recurrence<V> {
+    pub fn from_connection<A: WallArithmetic<Value = V>>(arithmetic: &A, connection: &[V]) -> Self {
+        Recurrence { coefficients: connection.iter().skip(1).map(|c| arithmetic.neg(c)).collect() }
+    }
+
+    pub fn order(&self) -> usize {
+        self.coefficients.len()
+    }
+
+    // Whether term n follows from the terms before it, `None` if any of the terms is not known
+    pub fn holds_at<A, F>(&self, arithmetic: &A, terms: F, n: isize) -> Option<bool>
+        where
+            A: WallArithmetic<Value = V>,
+            F: Fn(isize) -> Option<V>
+    {
+        let mut sum = arithmetic.zero();
+        for (i, a) in self.coefficients.iter().enumerate() {
+            sum = arithmetic.add(&sum, &arithmetic.mul(a, &terms(n - i as isize - 1)?));
+        }
+        Some(arithmetic.is_zero(&arithmetic.sub(&terms(n)?, &sum)))
+    }
+
+    // Writes the recurrence like s(n) = 2 s(n-1) - s(n-2), `show` writes each coefficient
+    pub fn format<A: WallArithmetic<Value = V>>(&self, arithmetic: &A, show: impl Fn(&V) -> String) -> String {
+        let mut text = "s(n) =".to_owned();
+        let mut first = true;
+        for (i, a) in self.coefficients.iter().enumerate().filter(|(_, a)| !arithmetic.is_zero(a)) {
+            let coefficient = show(a);
+            let (sign, magnitude) = match coefficient.strip_prefix('-') {
+                Some(magnitude) => ("-", magnitude.to_owned()),
+                None => ("+", coefficient)
+            };
+            text.push_str(&match (first, sign) {
+                (true, "+") => " ".to_owned(),
+                (true, _) => " -".to_owned(),
+                (false, _) => format!(" {sign} ")
+            });
+            if magnitude != "1" {
+                text.push_str(&format!("{magnitude} "));
+            }
+            text.push_str(&format!("s(n-{})", i + 1));
+            first = false;
+        }
+        if first {
+            text.push_str(" 0");
+        }
+        text
+    }
+}
+
+// The sizes k where the profile disagrees with the wall. k is a value of the profile exactly when the Hankel determinant
+// det(s(i+j)) of size k is not zero, and that is the cell of the wall in row k+1 and k-1 columns right of the first term.
+// `is_zero(k)` says whether that cell is zero, `None` if it is outside of the wall. Only the sizes whose determinant
+// uses no more terms than the profile are compared, the number of them is returned with the disagreements.
+pub fn check_against_wall<Z>(profile: &[usize], is_zero: Z) -> (usize, Vec<usize>)
+    where
+        Z: Fn(usize) -> Option<bool>
+{
+    let mut checked = 0;
+    let mut mismatches = vec![];
+    for k in (1..).take_while(|k| 2*k - 1 <= profile.len()) {
+        let Some(zero) = is_zero(k) else {
+            continue;
+        };
+        checked += 1;
+        if zero == profile.contains(&k) {
+            mismatches.push(k);
+        }
+    }
+    (checked, mismatches)
+}
+
+pub fn write_csv(profile: &[usize], path: &str) -> Result<(), String> {
+    let mut csv = "terms,complexity\n".to_owned();
+    for (n, l) in profile.iter().enumerate() {
+        csv.push_str(&format!("{},{l}\n", n + 1));
+    }
+    fs::write(path, csv).map_err(|e| format!("Could not write {path}: {e}"))
+}
+
+// Plots the profile as a step line against the number of terms, with the line n/2 that a random sequence follows
+pub fn write_svg(profile: &[usize], path: &str) -> Result<(), String> {
+    let n = profile.len();
+    let height = profile.iter().copied().max().unwrap_or(0).max(n.div_ceil(2)).max(1);
+    let margin = 2;
+    let (width, full_height) = (n + 2*margin, height + 2*margin);
+    let mut svg = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"-{margin} -{margin} {width} {full_height}\" width=\"{}\" height=\"{}\">\n",
+        width * 4, full_height * 4);
+    // y is flipped so that the complexity goes up
+    svg.push_str(&format!("<g transform=\"translate(0 {height}) scale(1 -1)\" fill=\"none\" stroke-width=\"0.3\">\n"));
+    svg.push_str(&format!("<path d=\"M 0 0 H {n} M 0 0 V {height}\" stroke=\"#000000\"/>\n"));
+    svg.push_str(&format!("<path d=\"M 0 0 L {n} {}\" stroke=\"#888888\" stroke-dasharray=\"1 1\"/>\n", n as f64 / 2.0));
+    // the complexity of the first i terms, which can only change at i
+    let mut steps = "M 0 0".to_owned();
+    for (i, l) in profile.iter().enumerate() {
+        steps.push_str(&format!(" H {} V {l}", i + 1));
+    }
+    svg.push_str(&format!("<path d=\"{steps}\" stroke=\"#ff0000\"/>\n"));
+    svg.push_str("</g>\n</svg>\n");
+    fs::write(path, svg).map_err(|e| format!("Could not write {path}: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use super::*;
+    use crate::big_wall::BigArithmetic;
+
+    fn terms(values: &[i64]) -> Vec<BigInt> {
+        values.iter().map(|&v| BigInt::from(v)).collect()
+    }
+
+    #[test]
+    fn fibonacci_has_complexity_two() {
+        let arithmetic = BigArithmetic::new(7.into());
+        let complexity = berlekamp_massey(&arithmetic, &terms(&[0, 1, 1, 2, 3, 5, 1, 6, 0, 6])).unwrap();
+        assert_eq!(complexity.profile, vec![0, 2, 2, 2, 2, 2, 2, 2, 2, 2]);
+        // s(n) - s(n-1) - s(n-2) = 0 mod 7
+        assert_eq!(complexity.connection, terms(&[1, 6, 6]));
+    }
+
+    #[test]
+    fn zeros_then_a_one() {
+        let arithmetic = BigArithmetic::new(2.into());
+        let complexity = berlekamp_massey(&arithmetic, &terms(&[0, 0, 0, 1])).unwrap();
+        assert_eq!(complexity.profile, vec![0, 0, 0, 4]);
+        assert_eq!(complexity.connection.len(), 5);
+    }
+
+    #[test]
+    fn geometric_sequence() {
+        let arithmetic = BigArithmetic::new(11.into());
+        let complexity = berlekamp_massey(&arithmetic, &terms(&[1, 3, 9, 5, 4, 1])).unwrap();
+        assert_eq!(complexity.profile, vec![1; 6]);
+        // s(n) - 3 s(n-1) = 0 mod 11
+        assert_eq!(complexity.connection, terms(&[1, 8]));
+    }
+
+    #[test]
+    fn the_profile_agrees_with_the_wall() {
+        // 0, 0, 0, 1 has non zero Hankel determinants of sizes 0 and 4 only, and 4 uses more terms than there are
+        let profile = [0, 0, 0, 4];
+        assert_eq!(check_against_wall(&profile, |k| Some(k != 4)), (2, vec![]));
+        assert_eq!(check_against_wall(&profile, |k| Some(k == 2)), (2, vec![1]));
+        assert_eq!(check_against_wall(&profile, |k| (k == 2).then_some(false)), (1, vec![2]));
+    }
+
+    #[test]
+    fn writes_the_profile() {
+        let path = std::env::temp_dir().join("complexity_test.csv").to_string_lossy().into_owned();
+        write_csv(&[0, 2, 2], &path).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "terms,complexity\n1,0\n2,2\n3,2\n");
+    }
+
+    #[test]
+    fn recurrences() {
+        let arithmetic = BigArithmetic::new(7.into());
+        let fibonacci = Recurrence::from_connection(&arithmetic, &terms(&[1, 6, 6]));
+        assert_eq!((fibonacci.order(), fibonacci.format(&arithmetic, |a| a.to_string())), (2, "s(n) = s(n-1) + s(n-2)".to_owned()));
+        let values = terms(&[0, 1, 1, 2, 3, 5, 1, 0]);
+        let term = |n: isize| usize::try_from(n).ok().and_then(|i| values.get(i)).cloned();
+        assert_eq!((2..8).map(|n| fibonacci.holds_at(&arithmetic, term, n)).collect::<Vec<_>>(), vec![Some(true), Some(true), Some(true), Some(true), Some(true), Some(false)]);
+        assert_eq!(fibonacci.holds_at(&arithmetic, term, 1), None);
+
+        let integers = BigArithmetic::new(0.into());
+        let format = |coefficients: &[i64]| Recurrence { coefficients: terms(coefficients) }.format(&integers, |a| a.to_string());
+        assert_eq!(format(&[2, -1]), "s(n) = 2 s(n-1) - s(n-2)");
+        assert_eq!(format(&[-1, 0, 3]), "s(n) = -s(n-1) + 3 s(n-3)");
+        assert_eq!(format(&[0]), "s(n) = 0");
+    }
+}
@@ -0,0 +1,244 @@
+use num_bigint::BigInt;
+use num_traits::{One, Zero};
+
+use crate::Boundary;
+use crate::big_wall::{self, BigArithmetic};
+use crate::finite_wall::DefinedRegion;
+
+const TRIAL_DIVISION_LIMIT: i128 = 1_000_000;
+const MILLER_RABIN_BASES: [u32; 20] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71];
+
+fn gcd(mut a: i128, mut b: i128) -> i128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a.abs()
+}
+
+pub fn is_prime(n: i128) -> bool {
+    if n < 2 {
+        return false;
+    }
+    for p in MILLER_RABIN_BASES {
+        let p = p as i128;
+        if n % p == 0 {
+            return n == p;
+        }
+    }
+
+    let n_big = BigInt::from(n);
+    let n_minus_one = &n_big - 1;
+    let twos = (n-1).trailing_zeros();
+    let odd = &n_minus_one >> twos;
+    'bases: for base in MILLER_RABIN_BASES {
+        let mut x = BigInt::from(base).modpow(&odd, &n_big);
+        if x.is_one() || x == n_minus_one {
+            continue;
+        }
+        for _ in 1..twos {
+            x = (&x * &x) % &n_big;
+            if x == n_minus_one {
+                continue 'bases;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+// The prime factors of `n` and their exponents, factors above the trial division limit have to be prime
+pub fn factorise(mut n: i128) -> Result<Vec<(i128, u32)>, String> {
+    let original = n;
+    let mut factors = vec![];
+    let mut p = 2;
+    while p <= TRIAL_DIVISION_LIMIT && p*p <= n {
+        let mut exponent = 0;
+        while n % p == 0 {
+            n /= p;
+            exponent += 1;
+        }
+        if exponent > 0 {
+            factors.push((p, exponent));
+        }
+        p += if p == 2 { 1 } else { 2 };
+    }
+    if n > 1 {
+        if !is_prime(n) {
+            return Err(format!("could not factorise the modulo {original}, {n} has no factors below {TRIAL_DIVISION_LIMIT} but is not prime"));
+        }
+        factors.push((n, 1));
+    }
+    Ok(factors)
+}
+
+// The first cell of an i128 wall mod a composite `modulo` whose value needed a division by an entry with no inverse,
+// as its row and column in `lines` and the entry, `None` if every division was possible.
+// The cross rule divides by the cell two rows above, zero entries are not divided by as the frame theorems are used instead,
+// and those divide by the inner frame around the window, for the cells below it.
+// `top` is the row of the first line, the rows before row 3 are not computed so they need no division.
+pub fn find_non_invertible(lines: &[Vec<i128>], top: usize, modulo: i128) -> Option<(usize, usize, i128)> {
+    let reduce = |n: i128| n.rem_euclid(modulo);
+    let non_unit = |n: i128| n != 0 && gcd(n, modulo) != 1;
+    let first = 3usize.saturating_sub(top).max(2);
+
+    let mut found = (first..lines.len()).find_map(|y| {
+        lines[y-2].iter().map(|n| reduce(*n)).enumerate().find(|(_, n)| non_unit(*n)).map(|(x, n)| (y, x, n))
+    });
+    for y in 0..lines.len() {
+        for x in 0..lines[y].len() {
+            if reduce(lines[y][x]) != 0 {
+                continue;
+            }
+            let mut below = y+1;
+            while below < lines.len() && reduce(lines[below][x]) == 0 {
+                below += 1;
+            }
+            if below < first || below >= lines.len() || found.is_some_and(|(f, _, _)| f <= below) {
+                continue;
+            }
+            // the bottom of the inner frame is only divided by for the outer frame, one row further down
+            for (frame_y, line) in lines.iter().enumerate().take(y+2).skip(y.saturating_sub(1)) {
+                let needed_by = if frame_y < below { below } else { below+1 };
+                if needed_by >= lines.len() || found.is_some_and(|(f, _, _)| f <= needed_by) {
+                    continue;
+                }
+                let frame = line[x.saturating_sub(1)..=(x+1).min(line.len()-1)].iter().map(|n| reduce(*n)).find(|n| non_unit(*n));
+                if let Some(n) = frame {
+                    found = Some((needed_by, x, n));
+                }
+            }
+        }
+    }
+    found
+}
+
+// The wall mod `modulo` computed mod each of its prime power factors, and put back together with the chinese remainder theorem.
+// Mod a prime the cross rule and the frame theorems work as usual. Mod a prime power p^e they would need divisions by multiples of p,
+// so that part is the integer wall of the same terms reduced mod p^e, as every cell is a determinant of the terms.
+// A cell is `None` when any of its parts could not be found, which only happens when a term or a frame it needs is not known.
+pub fn composite_wall_lines<F>(sequence: F, modulo: i128, boundary: &Boundary) -> Result<Vec<Vec<Option<BigInt>>>, String>
+    where
+        F: Fn(isize) -> Option<BigInt>
+{
+    let factors = factorise(modulo)?;
+    let modulo_big = BigInt::from(modulo);
+    let terms = |n: isize| sequence(n).map(|v| ((v % &modulo_big) + &modulo_big) % &modulo_big);
+
+    let mut integer_wall: Option<Vec<Vec<Option<BigInt>>>> = None;
+    let mut lines: Option<Vec<Vec<Option<BigInt>>>> = None;
+    for (prime, exponent) in factors {
+        let factor = BigInt::from(prime).pow(exponent);
+        let part = if exponent == 1 {
+            big_wall::big_wall_lines(&terms, &BigArithmetic::new(factor.clone()), boundary)?
+        } else {
+            if integer_wall.is_none() {
+                integer_wall = Some(big_wall::big_wall_lines(&terms, &BigArithmetic::new(BigInt::zero()), boundary)?);
+            }
+            integer_wall.as_ref().unwrap().iter()
+                .map(|line| line.iter().map(|v| v.as_ref().map(|v| ((v % &factor) + &factor) % &factor)).collect())
+                .collect()
+        };
+
+        // the coefficient that is 1 mod this factor and 0 mod the others
+        let rest = &modulo_big / &factor;
+        let coefficient = &rest * big_wall::mod_inverse(&rest, &factor).unwrap();
+        let weighted = part.into_iter()
+            .map(|line| line.into_iter().map(|v| v.map(|v| v * &coefficient)).collect::<Vec<_>>());
+
+        lines = Some(match lines {
+            None => weighted.collect(),
+            Some(lines) => lines.into_iter().zip(weighted)
+                .map(|(a, b)| a.into_iter().zip(b).map(|(a, b)| Some((a? + b?) % &modulo_big)).collect())
+                .collect()
+        });
+    }
+
+    let lines = lines.unwrap_or_default().into_iter()
+        .map(|line| line.into_iter().map(|v| v.map(|v| v % &modulo_big)).collect())
+        .collect();
+    Ok(lines)
+}
+
+// Warns about the cells inside `region` that could not be found
+pub fn report_ill_defined(lines: &[Vec<Option<BigInt>>], boundary: &Boundary, modulo: i128, region: Option<DefinedRegion>) {
+    let ill_defined: Vec<(usize, isize)> = lines.iter().enumerate()
+        .flat_map(|(y, line)| line.iter().enumerate().filter(|(_, v)| v.is_none()).map(move |(x, _)| (boundary.top + y, boundary.left + x as isize)))
+        .filter(|(row, column)| region.is_none_or(|r| r.contains(*row, *column)))
+        .collect();
+    if let Some((row, column)) = ill_defined.first() {
+        eprintln!("warning: {} cells are ill-defined mod {modulo}, the first is at row {row}, column {column}", ill_defined.len());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::determinant;
+    use crate::random_sequence::RandomSequence;
+
+    #[test]
+    fn primes() {
+        for p in [2, 3, 5, 71, 73, 257, 1_000_003, 2_147_483_647, 1_000_000_000_000_000_003] {
+            assert!(is_prime(p), "{p} is prime");
+        }
+        for n in [-7, 0, 1, 4, 91, 561, 1_000_001, 3_215_031_751, 1_000_000_007 * 1_000_000_009] {
+            assert!(!is_prime(n), "{n} is not prime");
+        }
+    }
+
+    #[test]
+    fn factorisations() {
+        assert_eq!(factorise(360).unwrap(), vec![(2, 3), (3, 2), (5, 1)]);
+        assert_eq!(factorise(1_000_003).unwrap(), vec![(1_000_003, 1)]);
+        assert_eq!(factorise(2 * 1_000_000_000_000_000_003).unwrap(), vec![(2, 1), (1_000_000_000_000_000_003, 1)]);
+        assert!(factorise(1_000_000_007 * 1_000_000_009).is_err());
+    }
+
+    // Checks every cell of the composite wall against its Toeplitz determinant over the integers reduced mod `modulo`
+    fn check_against_determinants(modulo: i128, terms: impl Fn(isize) -> Option<BigInt>, boundary: &Boundary) {
+        let lines = composite_wall_lines(&terms, modulo, boundary).unwrap();
+        let integers = BigArithmetic::new(BigInt::from(0));
+        let m = BigInt::from(modulo);
+        for (y, line) in lines.iter().enumerate() {
+            let row = boundary.top + y;
+            for (x, cell) in line.iter().enumerate() {
+                let column = boundary.left + x as isize;
+                let expected = if row == 0 { Some(BigInt::from(0)) } else { determinant::toeplitz(&integers, &terms, column, row - 1).unwrap() };
+                assert_eq!(cell, &expected.map(|v| ((v % &m) + &m) % &m), "row {row}, column {column} mod {modulo}");
+            }
+        }
+    }
+
+    #[test]
+    fn composite_walls_match_their_determinants() {
+        let boundary = Boundary { top: 0, bottom: 14, left: -10, right: 10 };
+        for (seed, modulo) in [(1, 4), (2, 4), (3, 12), (4, 12), (5, 8), (6, 9)] {
+            let random = RandomSequence::new(seed, modulo as u64, 0.6);
+            check_against_determinants(modulo, |n| Some(random.value(n).into()), &boundary);
+        }
+    }
+
+    #[test]
+    fn every_cell_of_a_two_sided_wall_is_defined() {
+        let boundary = Boundary { top: 0, bottom: 20, left: 0, right: 20 };
+        let random = RandomSequence::new(9, 4, 0.75);
+        let lines = composite_wall_lines(|n| Some(random.value(n).into()), 4, &boundary).unwrap();
+        assert!(lines.iter().flatten().all(|v| v.is_some()));
+    }
+
+    #[test]
+    fn non_invertible_divisions() {
+        // the cross rule divides by the 3 at row 2, column 1 for the cell at row 4
+        let lines = vec![vec![0; 4], vec![1; 4], vec![1, 3, 1, 1], vec![5, 5, 5, 5], vec![1; 4]];
+        assert_eq!(find_non_invertible(&lines, 0, 6), Some((4, 1, 3)));
+        // zeros are not divided by, but the 2 on the bottom of the frame of the window is, for the outer frame below it
+        let lines = vec![vec![0; 4], vec![1; 4], vec![1, 0, 1, 1], vec![1, 1, 2, 1]];
+        assert_eq!(find_non_invertible(&lines, 0, 6), None);
+        let lines = vec![vec![0; 4], vec![1; 4], vec![1, 0, 1, 1], vec![1, 1, 2, 1], vec![1; 4]];
+        assert_eq!(find_non_invertible(&lines, 0, 6), Some((4, 1, 2)));
+        // the 3 on the side of the frame is divided by for the bottom of the frame
+        let lines = vec![vec![0; 4], vec![1; 4], vec![3, 0, 1, 1], vec![1, 1, 1, 1]];
+        assert_eq!(find_non_invertible(&lines, 0, 6), Some((3, 1, 3)));
+        assert_eq!(find_non_invertible(&lines, 0, 5), None);
+    }
+}
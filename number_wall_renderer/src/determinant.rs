@@ -0,0 +1,121 @@
+use crate::big_wall::WallArithmetic;
+
+// The determinant of a square matrix by fraction free elimination, whose divisions are all exact,
+// so it works over the integers as well as over a field
+pub fn determinant<A: WallArithmetic>(arithmetic: &A, mut matrix: Vec<Vec<A::Value>>) -> Result<A::Value, String> {
+    let n = matrix.len();
+    if n == 0 {
+        return Ok(arithmetic.one());
+    }
+    let mut negative = false;
+    let mut previous = arithmetic.one();
+    for k in 0..n {
+        let Some(pivot) = (k..n).find(|&r| !arithmetic.is_zero(&matrix[r][k])) else {
+            return Ok(arithmetic.zero());
+        };
+        if pivot != k {
+            matrix.swap(pivot, k);
+            negative = !negative;
+        }
+        for i in k+1..n {
+            for j in k+1..n {
+                let num = arithmetic.sub(&arithmetic.mul(&matrix[i][j], &matrix[k][k]), &arithmetic.mul(&matrix[i][k], &matrix[k][j]));
+                matrix[i][j] = arithmetic.div(&num, &previous)?;
+            }
+        }
+        previous = matrix[k][k].clone();
+    }
+    let value = matrix[n-1][n-1].clone();
+    Ok(if negative { arithmetic.neg(&value) } else { value })
+}
+
+// The determinant of the matrix of size `order` whose entry in row i and column j is the term `index(i, j)`,
+// `None` if a term it needs is not known
+fn sequence_determinant<A, F>(arithmetic: &A, sequence: F, order: usize, index: impl Fn(usize, usize) -> isize) -> Result<Option<A::Value>, String>
+    where
+        A: WallArithmetic,
+        F: Fn(isize) -> Option<A::Value>
+{
+    let mut matrix = Vec::with_capacity(order);
+    for i in 0..order {
+        let mut row = Vec::with_capacity(order);
+        for j in 0..order {
+            let Some(term) = sequence(index(i, j)) else {
+                return Ok(None);
+            };
+            row.push(term);
+        }
+        matrix.push(row);
+    }
+    determinant(arithmetic, matrix).map(Some)
+}
+
+// The Hankel determinant det(s(start+i+j)) of size `order`
+pub fn hankel<A, F>(arithmetic: &A, sequence: F, start: isize, order: usize) -> Result<Option<A::Value>, String>
+    where
+        A: WallArithmetic,
+        F: Fn(isize) -> Option<A::Value>
+{
+    sequence_determinant(arithmetic, sequence, order, |i, j| start + (i+j) as isize)
+}
+
+// The Toeplitz determinant det(s(centre+i-j)) of size `order`, which is the cell of the wall in row order+1 and column `centre`
+pub fn toeplitz<A, F>(arithmetic: &A, sequence: F, centre: isize, order: usize) -> Result<Option<A::Value>, String>
+    where
+        A: WallArithmetic,
+        F: Fn(isize) -> Option<A::Value>
+{
+    sequence_determinant(arithmetic, sequence, order, |i, j| centre + i as isize - j as isize)
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use super::*;
+    use crate::big_wall::BigArithmetic;
+
+    fn fibonacci(n: isize) -> Option<BigInt> {
+        let (mut a, mut b) = (BigInt::from(0), BigInt::from(1));
+        for _ in 0..n {
+            (a, b) = (b.clone(), a + b);
+        }
+        (n >= 0).then_some(a)
+    }
+
+    #[test]
+    fn determinant_of_small_matrices() {
+        let arithmetic = BigArithmetic::new(0.into());
+        let matrix = |rows: &[&[i64]]| rows.iter().map(|r| r.iter().map(|&v| BigInt::from(v)).collect()).collect();
+        assert_eq!(determinant(&arithmetic, vec![]).unwrap(), 1.into());
+        assert_eq!(determinant(&arithmetic, matrix(&[&[0, 1], &[1, 0]])).unwrap(), (-1).into());
+        assert_eq!(determinant(&arithmetic, matrix(&[&[2, -3, 1], &[2, 0, -1], &[1, 4, 5]])).unwrap(), 49.into());
+        assert_eq!(determinant(&arithmetic, matrix(&[&[1, 2], &[2, 4]])).unwrap(), 0.into());
+    }
+
+    #[test]
+    fn toeplitz_determinants() {
+        let arithmetic = BigArithmetic::new(0.into());
+        let naturals = |n: isize| Some(BigInt::from(n));
+        // det [[2, 1], [3, 2]]
+        assert_eq!(toeplitz(&arithmetic, naturals, 2, 2).unwrap(), Some(1.into()));
+        // det [[3, 2, 1], [4, 3, 2], [5, 4, 3]]
+        assert_eq!(toeplitz(&arithmetic, naturals, 3, 3).unwrap(), Some(0.into()));
+        assert_eq!(toeplitz(&arithmetic, naturals, 5, 0).unwrap(), Some(1.into()));
+        // the term before the fibonacci numbers start is not known
+        assert_eq!(toeplitz(&arithmetic, fibonacci, 0, 2).unwrap(), None);
+    }
+
+    #[test]
+    fn hankel_determinants() {
+        let arithmetic = BigArithmetic::new(0.into());
+        // det [[1, 1], [1, 2]]
+        assert_eq!(hankel(&arithmetic, fibonacci, 1, 2).unwrap(), Some(1.into()));
+        // the fibonacci numbers satisfy a recurrence of order 2, so the larger determinants are 0
+        assert_eq!(hankel(&arithmetic, fibonacci, 1, 3).unwrap(), Some(0.into()));
+        // det [[1, 0], [0, 1]] mod 2 of the finite sequence 1, 0, 1
+        let terms = |n: isize| [1, 0, 1].get(n as usize).map(|&v| BigInt::from(v));
+        assert_eq!(hankel(&BigArithmetic::new(2.into()), terms, 0, 2).unwrap(), Some(1.into()));
+        assert_eq!(hankel(&arithmetic, terms, 0, 3).unwrap(), None);
+    }
+}
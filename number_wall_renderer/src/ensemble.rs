@@ -0,0 +1,114 @@
+use std::{collections::BTreeMap, fs};
+use json::JsonValue;
+
+use crate::{Boundary, WallType};
+use crate::random_sequence::RandomSequence;
+use crate::zero_windows::{find_zero_windows, ZeroWindow};
+
+pub struct EnsembleSettings {
+    pub count: usize,
+    pub report_file: String
+}
+
+// Renders `count` random walls (seeds `seed`, `seed+1`, ...) and reports the average zero window statistics.
+// The walls are i128 walls that are not checked for overflow, so the modulo has to keep their products in an i128.
+pub fn run_ensemble(random: RandomSequence, wall_type: WallType, left_values: [i128; 2], modulo: i128, boundary: Boundary, settings: EnsembleSettings) -> Result<(), String> {
+    if modulo == 0 || modulo > i64::MAX.into() {
+        return Err(format!("ensemble mode needs a modulo from 1 to {}, the walls could overflow an i128 otherwise", i64::MAX));
+    }
+    let mut window_counts: BTreeMap<usize, usize> = BTreeMap::new();
+    let mut cut_off_count = 0;
+    let mut largest_total = 0;
+    let mut largest_overall: Option<(u64, ZeroWindow)> = None;
+
+    for i in 0..settings.count {
+        let sequence = random.with_seed(random.seed.wrapping_add(i as u64));
+        let lines = match wall_type {
+            WallType::LeftConst => crate::left_const_fn_wall_lines(|n| sequence.value(n as isize), left_values, modulo, &boundary),
+            WallType::BiDirectional => crate::bi_directional_fn_wall_lines(|n| sequence.value(n), modulo, &boundary),
+            WallType::Repeating => { return Err("Cannot use a random sequence with the repeating wall type".into()); }
+        };
+
+        let mut largest = 0;
+        for window in find_zero_windows(&lines, &boundary) {
+            if window.cut_off {
+                cut_off_count += 1;
+            } else {
+                *window_counts.entry(window.size).or_insert(0) += 1;
+                largest = largest.max(window.size);
+                if largest_overall.is_none_or(|(_, w)| window.size > w.size) {
+                    largest_overall = Some((sequence.seed, window));
+                }
+            }
+        }
+        largest_total += largest;
+    }
+
+    let walls = settings.count.max(1) as f64;
+    let mut mean_counts = JsonValue::new_object();
+    for (size, count) in &window_counts {
+        mean_counts[size.to_string()] = (*count as f64 / walls).into();
+    }
+
+    let report = json::object!{
+        walls: settings.count,
+        seed: random.seed,
+        alphabet: random.alphabet,
+        bias: random.bias,
+        modulo: modulo as u64,
+        mean_window_count_by_size: mean_counts,
+        mean_cut_off_window_count: cut_off_count as f64 / walls,
+        mean_largest_window_size: largest_total as f64 / walls,
+        largest_window: match largest_overall {
+            Some((seed, window)) => json::object!{
+                seed: seed,
+                top: window.top,
+                left: window.left,
+                size: window.size
+            },
+            None => JsonValue::Null
+        }
+    };
+
+    fs::write(&settings.report_file, report.pretty(4)).map_err(|e| format!("Could not write the ensemble report to {}: {e}", settings.report_file))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(name: &str, count: usize) -> EnsembleSettings {
+        EnsembleSettings { count, report_file: std::env::temp_dir().join(name).to_string_lossy().into_owned() }
+    }
+
+    fn boundary() -> Boundary {
+        Boundary { top: 0, bottom: 16, left: 0, right: 24 }
+    }
+
+    #[test]
+    fn rejects_walls_that_could_overflow() {
+        let random = RandomSequence::new(1, 2, 0.5);
+        for modulo in [0, i128::from(i64::MAX) + 1] {
+            assert!(run_ensemble(random, WallType::BiDirectional, [0, 0], modulo, boundary(), settings("ensemble_overflow.json", 1)).is_err());
+        }
+    }
+
+    #[test]
+    fn reports_the_windows_of_a_single_wall() {
+        let random = RandomSequence::new(5, 2, 0.5);
+        let settings = settings("ensemble_single.json", 1);
+        let report_file = settings.report_file.clone();
+        run_ensemble(random, WallType::BiDirectional, [0, 0], 2, boundary(), settings).unwrap();
+        let report = json::parse(&fs::read_to_string(report_file).unwrap()).unwrap();
+
+        let lines = crate::bi_directional_fn_wall_lines(|n| random.value(n), 2, &boundary());
+        let windows = find_zero_windows(&lines, &boundary());
+        let complete: Vec<&ZeroWindow> = windows.iter().filter(|w| !w.cut_off).collect();
+        let largest = complete.iter().map(|w| w.size).max().unwrap_or(0);
+        assert_eq!(report["walls"].as_usize(), Some(1));
+        assert_eq!(report["mean_largest_window_size"].as_f64(), Some(largest as f64));
+        assert_eq!(report["mean_cut_off_window_count"].as_f64(), Some((windows.len() - complete.len()) as f64));
+        let counted: f64 = report["mean_window_count_by_size"].entries().map(|(_, c)| c.as_f64().unwrap()).sum();
+        assert_eq!(counted, complete.len() as f64);
+    }
+}
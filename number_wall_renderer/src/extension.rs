@@ -0,0 +1,85 @@
+use std::rc::Rc;
+
+use crate::sequence_fn::SequenceFn;
+
+// What a finite sequence is outside of its values
+#[derive(Debug, Clone, Copy)]
+pub enum Extension {
+    // columns outside the values are not allowed
+    Error,
+    Zero,
+    Constant(i128),
+    // the values repeat forever
+    Periodic,
+    // the values are reflected at each end, without repeating the end value
+    Mirror,
+    // like `Mirror`, but the reflected values are negated
+    Antisymmetric
+}
+
+// Turns a finite sequence into a two sided one, sequences that are not finite are unchanged
+pub fn extend(sequence: SequenceFn, extension: Extension) -> SequenceFn {
+    let (first, last) = match (sequence.first, sequence.last, extension) {
+        (_, _, Extension::Error) => { return sequence; },
+        (Some(first), Some(last), _) => (first, last),
+        _ => { return sequence; }
+    };
+    let len = last-first+1;
+    let func = sequence.func;
+
+    let extended: Rc<dyn Fn(isize) -> i128> = match extension {
+        Extension::Error => unreachable!(),
+        Extension::Zero => Rc::new(move |n| if n < first || n > last { 0 } else { func(n) }),
+        Extension::Constant(c) => Rc::new(move |n| if n < first || n > last { c } else { func(n) }),
+        Extension::Periodic => Rc::new(move |n| func(first + (n-first).rem_euclid(len))),
+        Extension::Mirror | Extension::Antisymmetric => {
+            let negate = matches!(extension, Extension::Antisymmetric);
+            let period = 2*(len-1);
+            Rc::new(move |n| {
+                if period == 0 {
+                    return func(first);
+                }
+                let k = (n-first).rem_euclid(period);
+                if k < len {
+                    func(first + k)
+                } else if negate {
+                    -func(first + period - k)
+                } else {
+                    func(first + period - k)
+                }
+            })
+        }
+    };
+
+    SequenceFn { func: extended, first: None, last: None }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extended(extension: Extension) -> Vec<i128> {
+        let sequence = extend(SequenceFn::finite(vec![1, 2, 3], 0), extension);
+        assert_eq!((sequence.first, sequence.last), (None, None));
+        (-3..7).map(|n| sequence.value(n)).collect()
+    }
+
+    #[test]
+    fn extensions() {
+        assert_eq!(extended(Extension::Zero), vec![0, 0, 0, 1, 2, 3, 0, 0, 0, 0]);
+        assert_eq!(extended(Extension::Constant(5)), vec![5, 5, 5, 1, 2, 3, 5, 5, 5, 5]);
+        assert_eq!(extended(Extension::Periodic), vec![1, 2, 3, 1, 2, 3, 1, 2, 3, 1]);
+        assert_eq!(extended(Extension::Mirror), vec![2, 3, 2, 1, 2, 3, 2, 1, 2, 3]);
+        assert_eq!(extended(Extension::Antisymmetric), vec![2, 3, -2, 1, 2, 3, -2, 1, 2, 3]);
+    }
+
+    #[test]
+    fn single_values_and_unchanged_sequences() {
+        let single = extend(SequenceFn::finite(vec![4], 2), Extension::Mirror);
+        assert_eq!((-2..3).map(|n| single.value(n)).collect::<Vec<i128>>(), vec![4; 5]);
+        let finite = extend(SequenceFn::finite(vec![1, 2], 0), Extension::Error);
+        assert_eq!((finite.first, finite.last), (Some(0), Some(1)));
+        let infinite = extend(SequenceFn::one_sided(|n| n as i128), Extension::Zero);
+        assert_eq!((infinite.first, infinite.last), (Some(0), None));
+    }
+}
@@ -0,0 +1,40 @@
+// Which cells of a wall depend only on the available terms `first..=last` of a finite sequence.
+// Row 0 is the row of zeros above the wall, row 1 the row of ones and row 2 the sequence itself,
+// a cell in row r is a determinant of the terms within r-2 columns of it.
+// An end that is `None` is unbounded, like the left of a left const wall, which is fixed by its left values.
+#[derive(Debug, Clone, Copy)]
+pub struct DefinedRegion {
+    pub first: Option<isize>,
+    pub last: Option<isize>
+}
+
+impl DefinedRegion {
+    pub fn new(first: isize, len: usize) -> Self {
+        DefinedRegion { first: Some(first), last: Some(first + len as isize - 1) }
+    }
+
+    pub fn contains(&self, row: usize, column: isize) -> bool {
+        if row < 2 {
+            return true;
+        }
+        let reach = (row-2) as isize;
+        self.first.is_none_or(|f| column-reach >= f) && self.last.is_none_or(|l| column+reach <= l)
+    }
+
+    // the lowest row that still has a defined cell, `None` if the rows go on forever
+    pub fn bottom(&self) -> Option<usize> {
+        match (self.first, self.last) {
+            (Some(first), Some(last)) => Some(2 + ((last-first).max(0) / 2) as usize),
+            _ => None
+        }
+    }
+}
+
+// How the cells outside of the defined region are drawn
+#[derive(Debug, Clone, Copy)]
+pub enum UndefinedCells {
+    // they are drawn like any other cell
+    Shown,
+    Colour([u8; 3]),
+    Transparent
+}
@@ -0,0 +1,295 @@
+use std::fs;
+
+use crate::Boundary;
+use crate::big_wall::WallArithmetic;
+use crate::zero_windows::{find_zero_windows_by, ZeroWindow};
+
+pub struct FrameViolation {
+    pub window: ZeroWindow,
+    pub reason: String
+}
+
+pub struct FrameReport {
+    pub checked: usize,
+    // windows that are cut off by the boundary or by undefined cells, so their frames cannot be checked
+    pub incomplete: Vec<ZeroWindow>,
+    pub violations: Vec<FrameViolation>
+}
+
+impl FrameReport {
+    pub fn print(&self) {
+        for v in &self.violations {
+            eprintln!("warning: the window of size {} at row {}, column {} breaks the frame theorems: {}", v.window.size, v.window.top, v.window.left, v.reason);
+        }
+        eprintln!("{} windows checked, {} incomplete, {} break the frame theorems", self.checked, self.incomplete.len(), self.violations.len());
+    }
+}
+
+// How the frames of the windows are drawn over a wall
+#[derive(Debug, Clone, Copy)]
+pub struct FrameColours {
+    pub inner: [u8; 3],
+    pub outer: [u8; 3],
+    // the outline of each window, which is only drawn in svg output
+    pub outline: [u8; 3]
+}
+
+pub struct FrameOverlay {
+    pub colours: FrameColours,
+    pub svg_file: Option<String>,
+    // whether the ratio of each side of the inner frames is written in the svg
+    pub ratios: bool
+}
+
+// The sides of the frame at `distance` around a window, 1 for the inner frame and 2 for the outer frame, as top, left, right and bottom.
+// Each side runs from corner to corner, the top and bottom from left to right and the left and right from top to bottom.
+// `None` if the frame would be above row 0.
+pub fn frame_sides(window: &ZeroWindow, distance: usize) -> Option<[Vec<(usize, isize)>; 4]> {
+    let (t, l, g) = (window.top, window.left, window.size);
+    let top = t.checked_sub(distance)?;
+    let (left, right, bottom) = (l - distance as isize, l + (g + distance - 1) as isize, t + g + distance - 1);
+    let len = g + 2*distance;
+    Some([
+        (0..len).map(|k| (top, left + k as isize)).collect(),
+        (0..len).map(|k| (top + k, left)).collect(),
+        (0..len).map(|k| (top + k, right)).collect(),
+        (0..len).map(|k| (bottom, left + k as isize)).collect()
+    ])
+}
+
+// The colour the frames of the complete windows paint each cell of lines `width` wide and `height` high, `None` for cells outside of every frame.
+// Inner frames are painted over outer frames where the frames of neighbouring windows overlap.
+pub fn frame_paint(windows: &[ZeroWindow], boundary: &Boundary, width: usize, height: usize, colours: &FrameColours) -> Vec<Vec<Option<[u8; 3]>>> {
+    let mut paint = vec![vec![None; width]; height];
+    for (distance, colour) in [(2, colours.outer), (1, colours.inner)] {
+        for window in windows.iter().filter(|w| !w.cut_off) {
+            for (row, column) in frame_sides(window, distance).into_iter().flatten().flatten() {
+                let (Some(y), Ok(x)) = (row.checked_sub(boundary.top), usize::try_from(column - boundary.left)) else {
+                    continue;
+                };
+                if y < height && x < width {
+                    paint[y][x] = Some(colour);
+                }
+            }
+        }
+    }
+    paint
+}
+
+fn hex(colour: [u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", colour[0], colour[1], colour[2])
+}
+
+// Writes the cells of a wall as an svg with a unit square for each cell, leaving out the cells that are `None`.
+// Every complete window is outlined, and `labels` are written in the middle of the top, left, right and bottom of the inner frame of their window.
+pub fn write_svg(cells: &[Vec<Option<[u8; 3]>>], boundary: &Boundary, windows: &[ZeroWindow], outline: [u8; 3], labels: &[(ZeroWindow, Vec<String>)], path: &str) -> Result<(), String> {
+    let (width, height) = (cells.first().map_or(0, |l| l.len()), cells.len());
+    let mut svg = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {width} {height}\" width=\"{}\" height=\"{}\" shape-rendering=\"crispEdges\">\n", width*8, height*8);
+    for (y, line) in cells.iter().enumerate() {
+        // runs of cells of the same colour are drawn as one rectangle
+        let mut x = 0;
+        while x < line.len() {
+            let run = line[x..].iter().take_while(|c| **c == line[x]).count();
+            if let Some(colour) = line[x] {
+                svg.push_str(&format!("<rect x=\"{x}\" y=\"{y}\" width=\"{run}\" height=\"1\" fill=\"{}\"/>\n", hex(colour)));
+            }
+            x += run;
+        }
+    }
+    for w in windows.iter().filter(|w| !w.cut_off) {
+        svg.push_str(&format!("<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"0.15\"/>\n",
+            w.left - boundary.left, w.top - boundary.top, w.size, w.size, hex(outline)));
+    }
+    for (window, texts) in labels {
+        let Some(sides) = frame_sides(window, 1) else {
+            continue;
+        };
+        for (side, text) in sides.iter().zip(texts) {
+            let (row, column) = side[side.len() / 2];
+            svg.push_str(&format!("<text x=\"{}\" y=\"{}\" font-size=\"0.8\" text-anchor=\"middle\" dominant-baseline=\"central\" fill=\"{}\">{text}</text>\n",
+                (column - boundary.left) as f64 + 0.5, (row - boundary.top) as f64 + 0.5, hex(outline)));
+        }
+    }
+    svg.push_str("</svg>\n");
+    fs::write(path, svg).map_err(|e| format!("Could not write {path}: {e}"))
+}
+
+// The inner frame of a window, each side running from corner to corner.
+// The top and bottom run from left to right and the left and right from top to bottom.
+struct InnerFrame<V> {
+    top: Vec<V>,
+    left: Vec<V>,
+    right: Vec<V>,
+    bottom: Vec<V>
+}
+
+// Checks the frame theorems for every zero window in the lines of a wall over an integral domain:
+// the sides of the inner frame of a window of size g are geometric sequences of non zero cells,
+// and their ratios P along the top, Q down the left, R along the bottom and S down the right have PS/QR = (-1)^g.
+// Cells that are `None` or where `defined(row, column)` is false are ignored, as in `find_zero_windows_masked`.
+pub fn check_frames<A, D>(lines: &[Vec<Option<A::Value>>], boundary: &Boundary, arithmetic: &A, defined: D) -> FrameReport
+    where
+        A: WallArithmetic,
+        D: Fn(usize, isize) -> bool
+{
+    let cell = |row: usize, column: isize| -> Option<&A::Value> {
+        let y = row.checked_sub(boundary.top)?;
+        let x = usize::try_from(column - boundary.left).ok()?;
+        let value = lines.get(y)?.get(x)?.as_ref()?;
+        defined(row, column).then_some(value)
+    };
+    let equal = |a: &A::Value, b: &A::Value| arithmetic.is_zero(&arithmetic.sub(a, b));
+
+    let mut report = FrameReport { checked: 0, incomplete: vec![], violations: vec![] };
+    for window in find_zero_windows_by(lines, boundary, |c| c.as_ref().map(|v| arithmetic.is_zero(v)), &defined) {
+        let (t, l, g) = (window.top, window.left, window.size);
+        if window.cut_off {
+            report.incomplete.push(window);
+            continue;
+        }
+        let side = |cells: &Vec<(usize, isize)>| -> Option<Vec<A::Value>> {
+            cells.iter().map(|&(r, c)| cell(r, c).cloned()).collect()
+        };
+        let frame = frame_sides(&window, 1).and_then(|[top, left, right, bottom]| Some(InnerFrame {
+            top: side(&top)?,
+            left: side(&left)?,
+            right: side(&right)?,
+            bottom: side(&bottom)?
+        }));
+        let Some(frame) = frame else {
+            report.incomplete.push(window);
+            continue;
+        };
+        report.checked += 1;
+
+        let mut reasons = vec![];
+        let not_square = (0..g).any(|y| (0..g).any(|x| !cell(t+y, l+x as isize).is_some_and(|v| arithmetic.is_zero(v))));
+        if not_square {
+            reasons.push("its zeros do not form a square".to_owned());
+        }
+        for (name, cells) in [("top", &frame.top), ("left", &frame.left), ("right", &frame.right), ("bottom", &frame.bottom)] {
+            if cells.iter().any(|c| arithmetic.is_zero(c)) {
+                reasons.push(format!("its inner frame has a zero on the {name}"));
+            } else if cells.windows(3).any(|w| !equal(&arithmetic.mul(&w[0], &w[2]), &arithmetic.mul(&w[1], &w[1]))) {
+                reasons.push(format!("the {name} of its inner frame is not geometric"));
+            }
+        }
+        if reasons.is_empty() {
+            // PS/QR = (-1)^g, multiplied out so that no division is needed
+            let ps = arithmetic.product(&[&frame.top[1], &frame.right[1], &frame.left[0], &frame.bottom[0]]);
+            let qr = arithmetic.product(&[&frame.top[0], &frame.right[0], &frame.left[1], &frame.bottom[1]]);
+            let qr = if g % 2 == 1 { arithmetic.neg(&qr) } else { qr };
+            if !equal(&ps, &qr) {
+                reasons.push(format!("PS/QR is not {}", if g % 2 == 1 { -1 } else { 1 }));
+            }
+        }
+        if !reasons.is_empty() {
+            report.violations.push(FrameViolation { window, reason: reasons.join(", ") });
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_bigint::BigInt;
+    use crate::big_wall::{big_wall_lines, BigArithmetic};
+    use crate::random_sequence::RandomSequence;
+
+    const BOUNDARY: Boundary = Boundary { top: 0, bottom: 20, left: -15, right: 15 };
+
+    fn wall(seed: u64, prime: u64) -> (BigArithmetic, Vec<Vec<Option<BigInt>>>) {
+        let arithmetic = BigArithmetic::new(prime.into());
+        let random = RandomSequence::new(seed, prime, 0.4);
+        let lines = big_wall_lines(|n| Some(random.value(n).into()), &arithmetic, &BOUNDARY).unwrap();
+        (arithmetic, lines)
+    }
+
+    #[test]
+    fn walls_keep_the_frame_theorems() {
+        for (seed, prime) in [(1, 2), (2, 3), (3, 5), (4, 7)] {
+            let (arithmetic, lines) = wall(seed, prime);
+            let report = check_frames(&lines, &BOUNDARY, &arithmetic, |_, _| true);
+            assert!(report.checked > 0);
+            assert!(report.violations.is_empty(), "{} windows break the frame theorems mod {prime}", report.violations.len());
+            // the inner frame of a window that does not touch the edge is inside the boundary
+            assert!(report.incomplete.iter().all(|w| w.cut_off));
+        }
+    }
+
+    #[test]
+    fn changed_cells_break_the_frame_theorems() {
+        let (arithmetic, mut lines) = wall(2, 3);
+        // a window of size 2 or more, so the top of its inner frame has three cells to compare
+        let window = *find_zero_windows_by(&lines, &BOUNDARY, |c| c.as_ref().map(|v| arithmetic.is_zero(v)), |_, _| true).iter()
+            .find(|w| !w.cut_off && w.size >= 2 && w.top >= 2)
+            .unwrap();
+        let [top, ..] = frame_sides(&window, 1).unwrap();
+        let (row, column) = top[1];
+        let cell = &mut lines[row - BOUNDARY.top][(column - BOUNDARY.left) as usize];
+        // the other non zero value mod 3
+        *cell = cell.as_ref().map(|v| arithmetic.neg(v));
+        let report = check_frames(&lines, &BOUNDARY, &arithmetic, |_, _| true);
+        let violation = report.violations.iter().find(|v| (v.window.top, v.window.left) == (window.top, window.left)).unwrap();
+        assert!(violation.reason.contains("top of its inner frame is not geometric"), "{}", violation.reason);
+    }
+
+    #[test]
+    fn windows_without_a_known_frame_are_incomplete() {
+        let (arithmetic, lines) = wall(3, 5);
+        let windows = find_zero_windows_by(&lines, &BOUNDARY, |c| c.as_ref().map(|v| arithmetic.is_zero(v)), |_, _| true);
+        let hidden = windows.iter().find(|w| !w.cut_off).unwrap();
+        // the cell to the left of the window is part of its inner frame
+        let report = check_frames(&lines, &BOUNDARY, &arithmetic, |r, c| (r, c) != (hidden.top, hidden.left - 1));
+        assert!(report.incomplete.iter().any(|w| (w.top, w.left) == (hidden.top, hidden.left)));
+        assert!(report.violations.is_empty());
+    }
+
+    fn window(top: usize, left: isize, size: usize) -> ZeroWindow {
+        ZeroWindow { top, left, size, width: size, height: size, cut_off: false }
+    }
+
+    #[test]
+    fn sides_run_from_corner_to_corner() {
+        let [top, left, right, bottom] = frame_sides(&window(3, 0, 2), 1).unwrap();
+        assert_eq!(top, vec![(2, -1), (2, 0), (2, 1), (2, 2)]);
+        assert_eq!(left, vec![(2, -1), (3, -1), (4, -1), (5, -1)]);
+        assert_eq!(right, vec![(2, 2), (3, 2), (4, 2), (5, 2)]);
+        assert_eq!(bottom, vec![(5, -1), (5, 0), (5, 1), (5, 2)]);
+        assert_eq!(frame_sides(&window(3, 0, 2), 2).unwrap()[0].len(), 6);
+        assert!(frame_sides(&window(1, 0, 1), 2).is_none());
+    }
+
+    #[test]
+    fn inner_frames_are_painted_over_outer_frames() {
+        let colours = FrameColours { inner: [255, 0, 0], outer: [0, 0, 255], outline: [0, 160, 0] };
+        let boundary = Boundary { top: 1, bottom: 9, left: 0, right: 8 };
+        // the outer frame of each window crosses the inner frame of the other, the cut off window is not painted
+        let windows = [window(3, 2, 1), window(5, 3, 1), ZeroWindow { cut_off: true, ..window(1, 7, 1) }];
+        let paint = frame_paint(&windows, &boundary, 9, 9, &colours);
+        let cell = |row: usize, column: usize| paint[row - boundary.top][column];
+        let (inner, outer) = (Some(colours.inner), Some(colours.outer));
+        assert_eq!((cell(1, 0), cell(3, 2), cell(7, 5), cell(8, 0)), (outer, outer, outer, None));
+        assert_eq!((cell(3, 1), cell(3, 3), cell(4, 4), cell(5, 4)), (inner, inner, inner, inner));
+        assert_eq!((cell(2, 6), cell(1, 8), cell(9, 8)), (None, None, None));
+    }
+
+    #[test]
+    fn writes_svg() {
+        let boundary = Boundary { top: 1, bottom: 3, left: -1, right: 1 };
+        let cells = vec![vec![Some([0, 0, 0]); 3], vec![Some([0, 0, 0]), None, Some([0, 0, 0])], vec![Some([0, 0, 0]); 3]];
+        let windows = [window(2, 0, 1)];
+        let path = std::env::temp_dir().join("frames_test.svg").to_string_lossy().into_owned();
+        write_svg(&cells, &boundary, &windows, [0, 160, 0], &[(windows[0], vec!["1".into(), "2".into(), "3".into(), "4".into()])], &path).unwrap();
+        let svg = fs::read_to_string(&path).unwrap();
+        assert!(svg.contains(r##"<rect x="0" y="0" width="3" height="1" fill="#000000"/>"##));
+        assert!(svg.contains(r##"<rect x="2" y="1" width="1" height="1" fill="#000000"/>"##));
+        assert!(svg.contains(r##"<rect x="1" y="1" width="1" height="1" fill="none" stroke="#00a000""##));
+        // each label is in the middle cell of its side of the inner frame
+        for (label, x, y) in [(1, 1.5, 0.5), (2, 0.5, 1.5), (3, 2.5, 1.5), (4, 1.5, 2.5)] {
+            assert!(svg.contains(&format!(r#"x="{x}" y="{y}" font-size="0.8""#)) && svg.contains(&format!(">{label}</text>")));
+        }
+        assert_eq!(svg.matches("<text").count(), 4);
+    }
+}
@@ -0,0 +1,257 @@
+use crate::big_wall::WallArithmetic;
+use crate::composite;
+
+// The finite field GF(p^k), as the polynomials over GF(p) mod an irreducible polynomial of degree k.
+// The element c_0 + c_1 x + ... + c_(k-1) x^(k-1) is written as the integer c_0 + c_1 p + ... + c_(k-1) p^(k-1).
+pub struct GaloisField {
+    prime: i128,
+    // monic, with the coefficients from the constant term up
+    polynomial: Vec<i128>,
+    order: i128
+}
+
+// Polynomials over GF(p) are vectors of coefficients from the constant term up, without zeros at the top.
+// The prime is at most i64::MAX, so products of two coefficients fit in an i128.
+
+fn trim(mut a: Vec<i128>) -> Vec<i128> {
+    while a.last() == Some(&0) {
+        a.pop();
+    }
+    a
+}
+
+fn pow_mod(mut base: i128, mut exponent: i128, p: i128) -> i128 {
+    let mut result = 1;
+    while exponent > 0 {
+        if exponent % 2 == 1 {
+            result = result * base % p;
+        }
+        base = base * base % p;
+        exponent /= 2;
+    }
+    result
+}
+
+fn inverse_mod(a: i128, p: i128) -> i128 {
+    pow_mod(a, p-2, p)
+}
+
+fn poly_mul(a: &[i128], b: &[i128], p: i128) -> Vec<i128> {
+    if a.is_empty() || b.is_empty() {
+        return vec![];
+    }
+    let mut product = vec![0; a.len() + b.len() - 1];
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            product[i+j] = (product[i+j] + x * y) % p;
+        }
+    }
+    trim(product)
+}
+
+fn poly_rem(mut a: Vec<i128>, b: &[i128], p: i128) -> Vec<i128> {
+    let lead = inverse_mod(b[b.len()-1], p);
+    while a.len() >= b.len() {
+        let shift = a.len() - b.len();
+        let factor = a[a.len()-1] * lead % p;
+        for (i, c) in b.iter().enumerate() {
+            a[shift+i] = (a[shift+i] - factor * c).rem_euclid(p);
+        }
+        a = trim(a);
+    }
+    a
+}
+
+fn poly_gcd(mut a: Vec<i128>, mut b: Vec<i128>, p: i128) -> Vec<i128> {
+    while !b.is_empty() {
+        (a, b) = (b.clone(), poly_rem(a, &b, p));
+    }
+    a
+}
+
+// The smallest degree of a factor of the monic polynomial `f`, `None` if it is irreducible.
+// The product of the irreducible polynomials of degree d divides x^(p^d) - x, so f has a factor of degree d when they share one.
+fn factor_degree(f: &[i128], p: i128) -> Option<usize> {
+    let mut power = vec![0, 1];
+    for d in 1..=(f.len()-1)/2 {
+        // x^(p^d) from x^(p^(d-1)) by raising it to the power p
+        let (mut base, mut exponent, mut result) = (power, p, vec![1]);
+        while exponent > 0 {
+            if exponent % 2 == 1 {
+                result = poly_rem(poly_mul(&result, &base, p), f, p);
+            }
+            base = poly_rem(poly_mul(&base, &base, p), f, p);
+            exponent /= 2;
+        }
+        power = result;
+
+        let mut difference = power.clone();
+        difference.resize(difference.len().max(2), 0);
+        difference[1] = (difference[1] - 1).rem_euclid(p);
+        if poly_gcd(f.to_vec(), trim(difference), p).len() > 1 {
+            return Some(d);
+        }
+    }
+    None
+}
+
+impl GaloisField {
+    // The field GF(prime^k) for a `polynomial` of degree k, with its coefficients from the constant term up
+    pub fn new(prime: i128, polynomial: &[i128]) -> Result<GaloisField, String> {
+        if prime > i64::MAX.into() || !composite::is_prime(prime) {
+            return Err(format!("{prime} is not valid for the field prime, it must be a prime below {}", i64::MAX));
+        }
+        let polynomial = trim(polynomial.iter().map(|c| c.rem_euclid(prime)).collect());
+        if polynomial.len() < 2 {
+            return Err("the field polynomial must have a degree of at least 1".into());
+        }
+        let lead = inverse_mod(polynomial[polynomial.len()-1], prime);
+        let polynomial: Vec<i128> = polynomial.iter().map(|c| c * lead % prime).collect();
+
+        let degree = polynomial.len() - 1;
+        let order = (0..degree).try_fold(1i128, |q, _| q.checked_mul(prime))
+            .filter(|q| *q <= i64::MAX.into())
+            .ok_or(format!("GF({prime}^{degree}) has more than {} elements", i64::MAX))?;
+        if let Some(d) = factor_degree(&polynomial, prime) {
+            return Err(format!("the field polynomial is not irreducible mod {prime}, it has a factor of degree {d}"));
+        }
+        Ok(GaloisField { prime, polynomial, order })
+    }
+
+    fn degree(&self) -> usize {
+        self.polynomial.len() - 1
+    }
+
+    fn coefficients(&self, mut element: i128) -> Vec<i128> {
+        let mut coefficients = Vec::with_capacity(self.degree());
+        for _ in 0..self.degree() {
+            coefficients.push(element % self.prime);
+            element /= self.prime;
+        }
+        coefficients
+    }
+
+    fn element(&self, coefficients: &[i128]) -> i128 {
+        coefficients.iter().rev().fold(0, |e, c| e * self.prime + c)
+    }
+
+    fn map_coefficients(&self, a: &i128, b: &i128, f: impl Fn(i128, i128) -> i128) -> i128 {
+        let (a, b) = (self.coefficients(*a), self.coefficients(*b));
+        let combined: Vec<i128> = a.iter().zip(b).map(|(x, y)| f(*x, y).rem_euclid(self.prime)).collect();
+        self.element(&combined)
+    }
+
+    fn inverse(&self, a: &i128) -> i128 {
+        // a^(q-1) = 1 for every non zero a
+        let (mut base, mut exponent, mut result) = (*a, self.order - 2, 1);
+        while exponent > 0 {
+            if exponent % 2 == 1 {
+                result = self.mul(&result, &base);
+            }
+            base = self.mul(&base, &base);
+            exponent /= 2;
+        }
+        result
+    }
+}
+
+impl WallArithmetic for GaloisField {
+    type Value = i128;
+
+    fn zero(&self) -> i128 {
+        0
+    }
+
+    fn one(&self) -> i128 {
+        1
+    }
+
+    fn is_zero(&self, value: &i128) -> bool {
+        *value == 0
+    }
+
+    fn add(&self, a: &i128, b: &i128) -> i128 {
+        self.map_coefficients(a, b, |x, y| x + y)
+    }
+
+    fn sub(&self, a: &i128, b: &i128) -> i128 {
+        self.map_coefficients(a, b, |x, y| x - y)
+    }
+
+    fn mul(&self, a: &i128, b: &i128) -> i128 {
+        let product = poly_mul(&trim(self.coefficients(*a)), &trim(self.coefficients(*b)), self.prime);
+        let mut reduced = poly_rem(product, &self.polynomial, self.prime);
+        reduced.resize(self.degree(), 0);
+        self.element(&reduced)
+    }
+
+    fn div(&self, num: &i128, den: &i128) -> Result<i128, String> {
+        if *den == 0 {
+            return Err(format!("division by zero in GF({})", self.order));
+        }
+        Ok(self.mul(num, &self.inverse(den)))
+    }
+
+    fn term(&self, value: i128) -> Result<i128, String> {
+        if !(0..self.order).contains(&value) {
+            return Err(format!("{value} is not an element of GF({}), which are written as integers from 0 to {}", self.order, self.order - 1));
+        }
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::big_wall::big_wall_lines;
+    use crate::determinant;
+    use crate::random_sequence::RandomSequence;
+    use crate::Boundary;
+
+    #[test]
+    fn only_irreducible_polynomials_over_a_prime_make_a_field() {
+        assert!(GaloisField::new(4, &[1, 1, 1]).is_err());
+        assert!(GaloisField::new(2, &[1]).is_err());
+        // x^2 + 1 = (x + 1)^2 mod 2, but is irreducible mod 3
+        assert!(GaloisField::new(2, &[1, 0, 1]).is_err());
+        assert!(GaloisField::new(3, &[1, 0, 1]).is_ok());
+        // x^4 + x^2 + 1 = (x^2 + x + 1)^2 mod 2 has no roots
+        assert!(GaloisField::new(2, &[1, 0, 1, 0, 1]).is_err());
+        assert!(GaloisField::new(2, &[1, 1, 0, 0, 1]).is_ok());
+    }
+
+    #[test]
+    fn field_operations() {
+        // GF(4) with x^2 = x + 1, where x is written as 2
+        let field = GaloisField::new(2, &[1, 1, 1]).unwrap();
+        assert_eq!((field.add(&2, &3), field.mul(&2, &2), field.mul(&2, &3)), (1, 3, 1));
+        for field in [field, GaloisField::new(3, &[2, 2, 1]).unwrap(), GaloisField::new(2, &[1, 1, 0, 1]).unwrap()] {
+            for a in 1..field.order {
+                assert_eq!(field.mul(&a, &field.div(&1, &a).unwrap()), 1);
+                assert_eq!(field.add(&field.sub(&0, &a), &a), 0);
+            }
+            assert!(field.div(&1, &0).is_err());
+            assert!(field.term(field.order).is_err());
+        }
+    }
+
+    #[test]
+    fn field_walls_match_their_determinants() {
+        let boundary = Boundary { top: 0, bottom: 12, left: -8, right: 8 };
+        for (seed, prime, polynomial) in [(1, 2, vec![1, 1, 1]), (2, 3, vec![1, 0, 1]), (3, 2, vec![1, 1, 0, 1])] {
+            let field = GaloisField::new(prime, &polynomial).unwrap();
+            // mostly zeros, so there are windows
+            let random = RandomSequence::new(seed, field.order as u64, 0.5);
+            let terms = |n| Some(random.value(n));
+            let lines = big_wall_lines(terms, &field, &boundary).unwrap();
+            for (y, line) in lines.iter().enumerate() {
+                let row = boundary.top + y;
+                for (x, cell) in line.iter().enumerate() {
+                    let column = boundary.left + x as isize;
+                    let expected = if row == 0 { Some(0) } else { determinant::toeplitz(&field, terms, column, row - 1).unwrap() };
+                    assert_eq!(cell, &expected, "row {row}, column {column} in GF({})", field.order);
+                }
+            }
+        }
+    }
+}
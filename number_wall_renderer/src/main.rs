@@ -9,30 +9,179 @@ use number_wall_generator::{
     repeating_sequence_wall, left_const_wall, bi_directional_wall
 };
 
-mod left_const_functions;
-mod bi_directional_functions;
+mod sequences;
+mod random_sequence;
+mod zero_windows;
+mod ensemble;
+mod sequence_file;
+mod finite_wall;
+mod period;
+mod oeis_search;
+mod sequence_fn;
+mod transforms;
+mod combine;
+mod extension;
+mod big_wall;
+mod overflow;
+mod composite;
+mod galois_field;
+mod valuation;
+mod determinant;
+mod rational;
+mod qd_table;
+mod symbolic;
+mod verify;
+mod frames;
+mod complexity;
+mod randomness;
+
+use std::rc::Rc;
+use sequences::SequenceSource;
+use random_sequence::RandomSequence;
+use sequence_file::{BinaryUnit, LoadedSequence, SequenceFileFormat};
+use sequence_fn::SequenceFn;
+use transforms::Transform;
+use combine::Combination;
+use extension::Extension;
+use period::Periodic;
+use ensemble::EnsembleSettings;
+use finite_wall::{DefinedRegion, UndefinedCells};
+use zero_windows::{WindowFileFormat, WindowSizeColours, ZeroWindowSettings};
+use frames::{FrameColours, FrameOverlay};
+use complexity::{ComplexitySettings, Recurrence};
+use std::ops::RangeInclusive;
+use num_traits::Zero;
+use big_wall::{BigArithmetic, WallArithmetic};
+use num_bigint::BigInt;
+use overflow::OverflowPolicy;
+use galois_field::GaloisField;
+use valuation::ValuationColours;
+use rational::RationalArithmetic;
+use num_rational::BigRational;
+use qd_table::{QdTableSettings, TableLayout};
+use symbolic::{SymbolicArithmetic, SymbolicColouring, SymbolicSettings, TableFormat};
+use verify::VerifySettings;
+use randomness::RandomnessSettings;
 
 const DEFAULT_MODULO: i128 = 0;
 const DEFAULT_OUTPUT_FILE: &'static str = "./out.png";
 const DEFAULT_LEFT_VALUES: [i128; 2] = [0, 0];
 const DEFAULT_SEQUENCE_START: isize = 0;
+const DEFAULT_RANDOM_SEED: u64 = 0;
+const DEFAULT_RANDOM_ALPHABET: u64 = 2;
+const DEFAULT_ENSEMBLE_REPORT_FILE: &str = "./ensemble_report.json";
 
 const DEFAULT_COLOURS: [(i128, [u8; 3]); 2] = [
     (0, [255, 255, 255]),
     (1, [0, 0, 0])
 ];
 const DEFAULT_DEFAULT_COLOUR: [u8; 3] = [128, 128, 128];
+const DEFAULT_VALUATION_ZERO_COLOUR: [u8; 3] = [255, 255, 255];
+const DEFAULT_VALUATION_LOW_COLOUR: [u8; 3] = [0, 0, 0];
+const DEFAULT_VALUATION_HIGH_COLOUR: [u8; 3] = [255, 0, 0];
+const DEFAULT_VALUATION_MAX: u32 = 8;
+const DEFAULT_SYMBOLIC_DEGREE_COLOUR: [u8; 3] = [255, 0, 0];
+const DEFAULT_WINDOW_SIZE_LOW_COLOUR: [u8; 3] = [255, 255, 255];
+const DEFAULT_WINDOW_SIZE_HIGH_COLOUR: [u8; 3] = [0, 0, 255];
+const DEFAULT_WINDOW_SIZE_MAX: usize = 32;
+const DEFAULT_INNER_FRAME_COLOUR: [u8; 3] = [255, 0, 0];
+const DEFAULT_OUTER_FRAME_COLOUR: [u8; 3] = [0, 0, 255];
+const DEFAULT_WINDOW_OUTLINE_COLOUR: [u8; 3] = [0, 160, 0];
+const DEFAULT_QD_DEPTH: usize = 8;
+const DEFAULT_VERIFY_SEED: u64 = 0;
+const DEFAULT_RANDOMNESS_REPORT_FILE: &str = "./randomness_report.json";
+// the block size that NIST SP 800-22 suggests for its linear complexity test, and its significance level
+const DEFAULT_COMPLEXITY_BLOCK_SIZE: usize = 500;
+const DEFAULT_SIGNIFICANCE: f64 = 0.01;
+// the region the built in sequences are checked over when verify is given no config
+const DEFAULT_VERIFY_BOUNDARY: Boundary = Boundary { top: 0, bottom: 24, left: 0, right: 48 };
 
 fn main() {
     let mut args = env::args();
 
-    let config_file = args.nth(1).expect("No argument was given");
+    let first_arg = args.nth(1).expect("No argument was given");
+    // either `<config>` to render a wall, `list`, `verify`, or `<command> <config>`
+    let (command, config_file) = match args.next() {
+        Some(config_file) => (first_arg, config_file),
+        None if first_arg == "list" => {
+            list_sequences();
+            return;
+        },
+        None if first_arg == "verify" => {
+            verify_builtin_sequences().unwrap();
+            return;
+        },
+        None => ("render".to_owned(), first_arg)
+    };
 
     let config = fs::read(config_file).unwrap();
 
-    let input = json::parse(&String::from_utf8(config).unwrap()).unwrap();
+    let input = json::parse(&quote_large_integers(&String::from_utf8(config).unwrap())).unwrap();
+
+    let result = match command.as_str() {
+        "render" => execute_input(&input, Mode::Render),
+        "verify" => get_verify_settings(&input).and_then(|settings| execute_input(&input, Mode::Verify(settings))),
+        "complexity" => get_complexity_settings(&input).and_then(|settings| execute_input(&input, Mode::Complexity(settings))),
+        "recurrence" => execute_input(&input, Mode::Recurrence),
+        "randomness" => get_randomness_settings(&input).and_then(|settings| execute_input(&input, Mode::Randomness(settings))),
+        "search" => execute_search(&input),
+        _ => Err(format!("{command} is not a valid command"))
+    };
+    // a term that could not be found is the cause of any other error
+    sequence_fn::check_terms().and(result).unwrap()
+}
+
+// Puts the integers of a config that are too large for a u64 in quotes. The JSON parser only keeps the first 19 or 20 digits of a number,
+// so they are read exactly as strings instead, like the values that are written as strings.
+fn quote_large_integers(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut quoted = String::with_capacity(text.len());
+    let (mut i, mut in_string) = (0, false);
+    while i < chars.len() {
+        let c = chars[i];
+        if in_string {
+            quoted.push(c);
+            if c == '\\' && i+1 < chars.len() {
+                quoted.push(chars[i+1]);
+                i += 1;
+            } else if c == '"' {
+                in_string = false;
+            }
+            i += 1;
+            continue;
+        }
+        if c == '"' {
+            in_string = true;
+        }
+        if !(c == '-' || c.is_ascii_digit()) {
+            quoted.push(c);
+            i += 1;
+            continue;
+        }
+        let start = i;
+        i += 1;
+        while i < chars.len() && chars[i].is_ascii_digit() {
+            i += 1;
+        }
+        let number: String = chars[start..i].iter().collect();
+        let digits = number.trim_start_matches('-');
+        let integer = !matches!(chars.get(i), Some('.' | 'e' | 'E'));
+        if integer && (digits.len() > 20 || (digits.len() == 20 && digits > "18446744073709551615")) {
+            quoted.push_str(&format!("\"{number}\""));
+        } else {
+            quoted.push_str(&number);
+        }
+    }
+    quoted
+}
 
-    execute_input(&input).unwrap()
+fn list_sequences() {
+    for sequence in sequences::builtin_sequences() {
+        let info = sequence.info();
+        let oeis = info.oeis.unwrap_or("-");
+        let modulo = info.natural_modulo.map_or("-".to_owned(), |m| m.to_string());
+        println!("{:<10} {oeis:<8} mod {modulo:<3} {}", info.name, info.description);
+    }
 }
 
 struct Boundary {
@@ -41,23 +190,68 @@ struct Boundary {
     left: isize,
     right: isize
 }
+#[derive(Clone, Copy)]
 enum WallType {
     Repeating,
     LeftConst,
     BiDirectional
 }
 enum Sequence {
-    Knight,
-    Rook,
-    Pagoda,
-    Rueppel,
-    Zigzag,
+    Named(Rc<dyn SequenceSource>),
+    Random(RandomSequence),
+    // custom values with a known start index
+    Indexed(LoadedSequence),
+    Combined(Box<Sequence>, Box<Sequence>, Combination),
     Custom(Vec<i128>)
 }
 
 struct Colours {
     mapping: HashMap<i128, [u8; 3]>,
-    default: [u8; 3]
+    default: [u8; 3],
+    // how cells that the terms of a finite sequence do not reach are drawn
+    undefined: UndefinedCells,
+    // the colour of cells that overflowed, if they are drawn differently
+    overflow: Option<[u8; 3]>,
+    // the colour of cells of a big integer wall that could not be found, the default colour if it is not given
+    ill_defined: Option<[u8; 3]>,
+    // colours cells by their p-adic valuation instead of by their value
+    valuation: Option<ValuationColours>,
+    // colours zero cells by the size of their window instead of by their value
+    window_size: Option<WindowSizeColours>,
+    // paints the inner and outer frames of the windows over the cells
+    frames: Option<FrameColours>
+}
+
+// What is done with the wall of a config
+enum Mode {
+    Render,
+    // checks the cells of the wall against their determinants
+    Verify(VerifySettings),
+    // finds the linear complexity profile of the terms of the region
+    Complexity(ComplexitySettings),
+    // finds the linear recurrences of the sequence from the windows that run to the bottom of the wall
+    Recurrence,
+    // renders the wall and tests the sequence like the output of a random number generator
+    Randomness(RandomnessSettings)
+}
+
+impl Mode {
+    fn name(&self) -> &'static str {
+        match self {
+            Mode::Render => "render",
+            Mode::Verify(_) => "verify",
+            Mode::Complexity(_) => "complexity",
+            Mode::Recurrence => "recurrence",
+            Mode::Randomness(_) => "randomness"
+        }
+    }
+}
+
+// The analyses that are run on a wall as well as rendering it
+struct WallAnalyses {
+    check_frames: bool,
+    zero_windows: Option<ZeroWindowSettings>,
+    frame_overlay: Option<FrameOverlay>
 }
 
 
@@ -78,39 +272,194 @@ fn get_wall_type(input: &JsonValue) -> Result<WallType, String> {
     }
 }
 
-fn get_sequence(input: &JsonValue) -> Result<Sequence, String> {
-    if input["sequence"].is_string() {
-        let sequence_name = input["sequence"].as_str().unwrap();
-        let sequence_name = sequence_name.to_ascii_lowercase();
-        if sequence_name == "knight" {
-            Ok(Sequence::Knight)
-        } else if sequence_name == "rook" {
-            Ok(Sequence::Rook)
-        } else if sequence_name == "pagoda" {
-            Ok(Sequence::Pagoda)
-        } else if sequence_name == "rueppel" {
-            Ok(Sequence::Rueppel)
-        } else if sequence_name == "zigzag" {
-            Ok(Sequence::Zigzag)
-        } else {
-            Err(format!("{sequence_name} is not a valid sequence"))
-        }
-    } else if input["sequence"].is_array() {
-        let mut sequence: Vec<i128> = vec![];
-        for pos in 0..input["sequence"].len() {
-            let val = &input["sequence"][pos];
-            if !val.is_number() {
-                return Err(format!("value at position {pos} in sequence is not a number"));
-            };
-            let v: i128 = match val.as_i64() {
+fn get_random_sequence(sequence: &JsonValue, modulo: i128) -> Result<RandomSequence, String> {
+    let seed = if sequence["seed"].is_null() {
+        DEFAULT_RANDOM_SEED
+    } else {
+        sequence["seed"].as_u64().ok_or(format!("{} is not valid for seed, it must be non negative and less than {}", sequence["seed"], u64::MAX))?
+    };
+
+    let alphabet = if !sequence["alphabet"].is_null() {
+        sequence["alphabet"].as_u64().ok_or(format!("{} is not valid for alphabet, it must be non negative and less than {}", sequence["alphabet"], u64::MAX))?
+    } else if modulo > 0 {
+        modulo as u64
+    } else {
+        DEFAULT_RANDOM_ALPHABET
+    };
+    if alphabet < 2 {
+        return Err("alphabet must be at least 2".into());
+    }
+
+    let bias = if sequence["bias"].is_null() {
+        (alphabet-1) as f64 / alphabet as f64
+    } else {
+        sequence["bias"].as_f64().ok_or(format!("{} is not valid for bias, it must be a number", sequence["bias"]))?
+    };
+    if !(0.0..=1.0).contains(&bias) {
+        return Err(format!("bias ({bias}) must be between 0 and 1"));
+    }
+
+    Ok(RandomSequence::new(seed, alphabet, bias))
+}
+
+fn get_sequence_file_format(sequence: &JsonValue) -> Result<SequenceFileFormat, String> {
+    match sequence["format"].as_str().map(|f| f.to_ascii_lowercase()).as_deref() {
+        None | Some("auto") => Ok(SequenceFileFormat::Auto),
+        Some("bfile") | Some("b_file") => Ok(SequenceFileFormat::BFile),
+        Some("list") => Ok(SequenceFileFormat::List),
+        Some(f) => Err(format!("{f} is not a valid sequence file format"))
+    }
+}
+
+fn get_sequence_file(sequence: &JsonValue, modulo: i128) -> Result<LoadedSequence, String> {
+    let path = sequence["path"].as_str().ok_or("sequence file path is not a string")?;
+    sequence_file::load_sequence_file(path, get_sequence_file_format(sequence)?, modulo)
+}
+
+fn get_binary_file(sequence: &JsonValue) -> Result<LoadedSequence, String> {
+    let path = sequence["path"].as_str().ok_or("binary file path is not a string")?;
+    let unit = match sequence["unit"].as_str().map(|u| u.to_ascii_lowercase()).as_deref() {
+        None | Some("bit") => BinaryUnit::Bit,
+        Some("byte") => BinaryUnit::Byte,
+        Some(u) => { return Err(format!("{u} is not a valid binary file unit, it must be bit or byte")); }
+    };
+    sequence_file::load_binary_file(path, unit)
+}
+
+fn get_combined_sequence(sequence: &JsonValue, modulo: i128) -> Result<Sequence, String> {
+    let a = get_sequence(&sequence["a"], modulo).map_err(|e| format!("in combined sequence a: {e}"))?;
+    let b = get_sequence(&sequence["b"], modulo).map_err(|e| format!("in combined sequence b: {e}"))?;
+
+    let combination = match sequence["operation"].as_str().map(|o| o.to_ascii_lowercase()).as_deref() {
+        Some("add") => Combination::Add,
+        Some("multiply") => Combination::Multiply,
+        Some("linear") => {
+            let coefficients = &sequence["coefficients"];
+            if !coefficients.is_array() || coefficients.len() != 2 {
+                return Err("coefficients for a linear combination must be an array of 2 numbers".into());
+            }
+            let c1 = coefficients[0].as_i64().ok_or(format!("Could not parse coefficient {} as an integer", coefficients[0]))?;
+            let c2 = coefficients[1].as_i64().ok_or(format!("Could not parse coefficient {} as an integer", coefficients[1]))?;
+            Combination::Linear(c1.into(), c2.into())
+        },
+        Some(o) => { return Err(format!("{o} is not a valid operation")); },
+        None => { return Err("combined sequence does not have an operation".into()); }
+    };
+
+    Ok(Sequence::Combined(Box::new(a), Box::new(b), combination))
+}
+
+fn get_sequence_values(values: &JsonValue, name: &str, modulo: i128) -> Result<Vec<i128>, String> {
+    if !values.is_array() {
+        return Err(format!("{name} is not an array"));
+    }
+    let mut parsed: Vec<i128> = vec![];
+    for pos in 0..values.len() {
+        let val = &values[pos];
+        let v: i128 = if val.is_string() {
+            sequence_file::parse_reduced_integer(val.as_str().unwrap(), modulo).map_err(|e| format!("value at position {pos} in {name}: {e}"))?
+        } else if val.is_number() {
+            match val.as_i64() {
                 Some(s) => s.into(),
-                None => { return Err(format!("unexpected error parsing number at position {pos} in sequence")); }
-            };
-            sequence.push(v);
+                None => parse_large_number(val, modulo).map_err(|e| format!("number at position {pos} in {name}: {e}"))?
+            }
+        } else {
+            return Err(format!("value at position {pos} in {name} is not a number"));
         };
-        Ok(Sequence::Custom(sequence))
+        parsed.push(v);
+    };
+    Ok(parsed)
+}
+
+// A JSON number too large for an i64, reduced by `modulo` like the values written as strings
+fn parse_large_number(value: &JsonValue, modulo: i128) -> Result<i128, String> {
+    sequence_file::parse_reduced_integer(&large_number_digits(value)?, modulo)
+}
+
+// The digits of a JSON integer that may be too large for an i64.
+// Integers too large for the JSON parser to keep every digit of are already strings, see `quote_large_integers`.
+fn large_number_digits(value: &JsonValue) -> Result<String, String> {
+    let JsonValue::Number(number) = value else {
+        return Err(format!("{value} is not a number"));
+    };
+    let (positive, mut mantissa, mut exponent) = number.as_parts();
+    while exponent < 0 && mantissa % 10 == 0 {
+        mantissa /= 10;
+        exponent += 1;
+    }
+    if exponent < 0 {
+        return Err(format!("{value} is not an integer"));
+    }
+    Ok(format!("{}{mantissa}{}", if positive { "" } else { "-" }, "0".repeat(exponent as usize)))
+}
+
+// A term of a custom sequence as a big integer, written as a number or a string
+fn parse_big_value(value: &JsonValue) -> Result<BigInt, String> {
+    if let Some(text) = value.as_str() {
+        return sequence_file::parse_big_integer(text);
+    }
+    if !value.is_number() {
+        return Err(format!("{value} is not a number"));
+    }
+    match value.as_i64() {
+        Some(v) => Ok(v.into()),
+        None => sequence_file::parse_big_integer(&large_number_digits(value)?)
+    }
+}
+
+// The terms of a custom sequence or a sequence file as big integers, if any of them is too large for an i128.
+// They are then computed without the i128 sequence functions, otherwise `None` and the sequence is used as usual.
+fn get_big_terms(sequence: &JsonValue) -> Result<Option<LoadedSequence<BigInt>>, String> {
+    let loaded = if sequence.is_array() || sequence["type"] == "custom" {
+        let (values, offset) = get_custom_terms(sequence, &parse_big_value)?;
+        LoadedSequence { values, offset }
+    } else if sequence["type"] == "file" {
+        let path = sequence["path"].as_str().ok_or("sequence file path is not a string")?;
+        sequence_file::load_big_sequence_file(path, get_sequence_file_format(sequence)?)?
+    } else {
+        return Ok(None);
+    };
+    Ok(loaded.values.iter().any(|v| i128::try_from(v).is_err()).then_some(loaded))
+}
+
+// `negative` holds the values at ..., -2, -1 and `positive` the values at 0, 1, 2, ...
+fn get_two_sided_custom(sequence: &JsonValue, modulo: i128) -> Result<LoadedSequence, String> {
+    let mut values = if sequence["negative"].is_null() {
+        vec![]
+    } else {
+        get_sequence_values(&sequence["negative"], "negative", modulo)?
+    };
+    let offset = -(values.len() as isize);
+    if !sequence["positive"].is_null() {
+        values.extend(get_sequence_values(&sequence["positive"], "positive", modulo)?);
+    }
+    if values.is_empty() {
+        return Err("custom sequence does not have any negative or positive values".into());
+    }
+    Ok(LoadedSequence { values, offset })
+}
+
+fn get_sequence(sequence: &JsonValue, modulo: i128) -> Result<Sequence, String> {
+    if sequence.is_string() {
+        let sequence_name = sequence.as_str().unwrap();
+        match sequences::find_sequence(sequence_name) {
+            Some(s) => Ok(Sequence::Named(s)),
+            None => Err(format!("{sequence_name} is not a valid sequence"))
+        }
+    } else if sequence.is_array() {
+        Ok(Sequence::Custom(get_sequence_values(sequence, "sequence", modulo)?))
+    } else if sequence.is_object() {
+        match sequence["type"].as_str() {
+            Some("random") => Ok(Sequence::Random(get_random_sequence(sequence, modulo)?)),
+            Some("file") => Ok(Sequence::Indexed(get_sequence_file(sequence, modulo)?)),
+            Some("binary") => Ok(Sequence::Indexed(get_binary_file(sequence)?)),
+            Some("custom") => Ok(Sequence::Indexed(get_two_sided_custom(sequence, modulo)?)),
+            Some("combine") => get_combined_sequence(sequence, modulo),
+            Some(t) => Err(format!("{t} is not a valid sequence type")),
+            None => Err("sequence object does not have a type".into())
+        }
     } else {
-        Err("sequence is not a string or an array".into())
+        Err("sequence is not a string, an array or an object".into())
     }
 }
 
@@ -118,12 +467,18 @@ fn get_modulo(input: &JsonValue) -> Result<i128, String> {
     if input["modulo"].is_null() {
         return Ok(DEFAULT_MODULO);
     };
+    if let Some(modulo) = input["modulo"].as_str() {
+        return match modulo.trim().parse::<i128>() {
+            Ok(m) if m >= 0 => Ok(m),
+            _ => Err(format!("error parsing modulo: {modulo} is not an integer between 0 and {}", i128::MAX))
+        };
+    }
     if !input["modulo"].is_number() {
         return Err("modulo is not a number".into());
     };
     match input["modulo"].as_u64() {
         Some(s) => Ok(s.into()),
-        None => Err(format!("error parsing modulo: it must not be negative or above {}", u64::MAX))
+        None => Err(format!("error parsing modulo: it must not be negative or above {}, larger moduli can be written as strings", u64::MAX))
     }
 }
 
@@ -196,318 +551,1645 @@ fn get_boundary(input: &JsonValue) -> Result<Boundary, String> {
     })
 }
 
-fn get_output_file(input: &JsonValue) -> Result<String, String> {
-    if input["output_file"].is_null() {
-        return Ok(DEFAULT_OUTPUT_FILE.into());
-    };
-    if !input["output_file"].is_string() {
-        return Err("output_file is not a string".into());
-    };
-    return Ok( input["output_file"].as_str().unwrap().to_owned() );
+// `"region": "auto"` fits the boundary to the defined cells of a finite sequence instead of reading it from the input
+fn get_auto_region(input: &JsonValue) -> Result<bool, String> {
+    if input["region"].is_null() {
+        return Ok(false);
+    }
+    match input["region"].as_str() {
+        Some(region) if region.eq_ignore_ascii_case("auto") => Ok(true),
+        _ => Err(format!("{} is not a valid region, the only region is \"auto\"", input["region"]))
+    }
 }
 
-fn get_sequence_start(input: &JsonValue) -> Result<isize, String> {
-    if input["sequence_start"].is_null() {
-        return Ok(DEFAULT_SEQUENCE_START);
-    };
-    if !input["sequence_start"].is_number() {
-        return Err("sequence_start is not a number".into());
+fn fit_boundary(input: &JsonValue, region: Option<DefinedRegion>) -> Result<Boundary, String> {
+    let region = region.ok_or("region auto needs a finite sequence")?;
+    let top = if input["top"].is_null() {
+        0
+    } else {
+        input["top"].as_usize().ok_or(format!("{} is not valid for top, is must be non negative and less than {}", input["top"], usize::MAX))?
     };
-    let ss: Result<isize, String> = input["sequence_start"].as_isize().ok_or("Could not parse sequence_start  as a number".into());
-    return Ok( ss?.to_owned() );
+    let left = region.first.unwrap_or(0);
+    let right = region.last.unwrap();
+    // without a first term the wall is only cut off on the right, the cells in column 0 are defined until row 2+right
+    let bottom = region.bottom().unwrap_or(2 + right.max(0) as usize);
+    if top > bottom {
+        return Err(format!("top ({top}) is below the last defined row ({bottom})"));
+    }
+    Ok(Boundary { top, bottom, left, right })
 }
 
-fn colour_to_u8_array(colour: &JsonValue) -> Result<[u8; 3], String> {
-    if colour.is_array() {
-        if colour.len() != 3 {
-            Err(format!("Colour array {colour} doesn't have 3 elements"))
-        } else {
-            Ok([
-                colour[0].as_u8().ok_or(format!("Could not parse element 0 of {colour} as a u8"))?,
-                colour[1].as_u8().ok_or(format!("Could not parse element 1 of {colour} as a u8"))?,
-                colour[2].as_u8().ok_or(format!("Could not parse element 2 of {colour} as a u8"))?,
-            ])
-        }
-    } else if colour.is_string() {
-        let colour = colour.as_str().unwrap();
-        if colour.len() == 3 {
-            Ok([
-                0x10 * u8::from_str_radix(&colour[0..=0], 16).ok().ok_or(format!("Could not parse character 0 of {colour} as a base 16 value") )?,
-                0x10 * u8::from_str_radix(&colour[1..=1], 16).ok().ok_or(format!("Could not parse character 1 of {colour} as a base 16 value") )?,
-                0x10 * u8::from_str_radix(&colour[2..=2], 16).ok().ok_or(format!("Could not parse character 2 of {colour} as a base 16 value") )?,
-            ])
-        } else if colour.len() == 4 {
-            Ok([
-                0x10 * u8::from_str_radix(&colour[1..=1], 16).ok().ok_or(format!("Could not parse character 1 of {colour} as a base 16 value"))?,
-                0x10 * u8::from_str_radix(&colour[2..=2], 16).ok().ok_or(format!("Could not parse character 2 of {colour} as a base 16 value"))?,
-                0x10 * u8::from_str_radix(&colour[3..=3], 16).ok().ok_or(format!("Could not parse character 3 of {colour} as a base 16 value"))?,
-            ])
-        } else if colour.len() == 6 {
-            Ok([
-                u8::from_str_radix(&colour[0..=1], 16).ok().ok_or(format!("Could not parse characters 0 and 1 of {colour} as a base 16 value"))?,
-                u8::from_str_radix(&colour[2..=3], 16).ok().ok_or(format!("Could not parse characters 2 and 3 of {colour} as a base 16 value"))?,
-                u8::from_str_radix(&colour[4..=5], 16).ok().ok_or(format!("Could not parse characters 4 and 5 of {colour} as a base 16 value"))?,
-            ])
-        } else if colour.len() == 7 {
-            Ok([
-                u8::from_str_radix(&colour[1..=2], 16).ok().ok_or(format!("Could not parse characters 1 and 2 of {colour} as a base 16 value"))?,
-                u8::from_str_radix(&colour[3..=4], 16).ok().ok_or(format!("Could not parse characters 3 and 4 of {colour} as a base 16 value"))?,
-                u8::from_str_radix(&colour[5..=6], 16).ok().ok_or(format!("Could not parse characters 5 and 6 of {colour} as a base 16 value"))?,
-            ])
-        } else {
-            Err(format!("Colour value {colour} is not of the correct type"))
-        }
-    } else {
-        Err(format!("Can't parse {colour} as a colour"))
+// `"mask"` turns the masking of undefined cells on or off, when it is on without an undefined colour they are transparent
+fn get_undefined_cells(input: &JsonValue, colours: &Colours) -> Result<UndefinedCells, String> {
+    if input["mask"].is_null() {
+        return Ok(colours.undefined);
+    }
+    match (input["mask"].as_bool(), colours.undefined) {
+        (Some(false), _) => Ok(UndefinedCells::Shown),
+        (Some(true), UndefinedCells::Shown) => Ok(UndefinedCells::Transparent),
+        (Some(true), undefined) => Ok(undefined),
+        (None, _) => Err("mask is not a bool".into())
     }
 }
 
-fn get_colours(input: &JsonValue) -> Result<Colours, String> {
-    let colours = &input["colours"];
-    if colours.is_null() {
-        let mut cols: HashMap<i128, [u8; 3]> = HashMap::new();
-        for col in DEFAULT_COLOURS {
-            cols.insert(col.0, col.1);
-        }
-        return Ok(Colours { mapping: cols, default: DEFAULT_DEFAULT_COLOUR });
-    };
-    if !colours.is_object() {
-        return Err("colours is not an object".into());
-    };
-    let mut default: [u8; 3] = DEFAULT_DEFAULT_COLOUR;
-    let mut cols: HashMap<i128, [u8; 3]> = HashMap::new();
-    for (value, col) in colours.entries() {
-        if value == "default" {
-            default = colour_to_u8_array(col)?;
-        } else {
-            match value.parse() {
-                Ok(v) => {
-                    cols.insert(v, colour_to_u8_array(col)?);
-                },
-                Err(_) => {
-                    return Err(format!("Cannot parse {value} as a number"));
-                }
-            }
-        }
+// Big integer walls are used by default when the values could overflow an i128,
+// that is for integer walls and for moduli whose products do not fit.
+// Left const walls are computed by the generator from their left values, which big integer walls do not have,
+// so they stay i128 walls and are checked for overflow instead.
+fn get_big_integers(input: &JsonValue, modulo: i128, wall_type: &WallType) -> Result<bool, String> {
+    if input["big_integers"].is_null() {
+        return Ok(!matches!(wall_type, WallType::LeftConst) && (modulo == 0 || modulo > i64::MAX.into()));
+    }
+    match input["big_integers"].as_bool() {
+        Some(true) if matches!(wall_type, WallType::LeftConst) => Err("big integers cannot be used with the left const wall type".into()),
+        Some(big) => Ok(big),
+        None => Err("big_integers is not a bool".into())
     }
-
-    Ok( Colours { mapping: cols, default } )
 }
 
-
-
-fn get_colour(value: i128, colours: &Colours) -> image::Rgb<u8> {
-    image::Rgb(*colours.mapping.get(&value).unwrap_or(&colours.default))
+// Composite moduli are split into prime powers, whose walls are computed separately and combined
+fn get_composite(input: &JsonValue, modulo: i128, wall_type: &WallType) -> Result<bool, String> {
+    if input["composite"].is_null() {
+        return Ok(false);
+    }
+    match input["composite"].as_bool() {
+        Some(true) if modulo < 2 => Err("composite needs a modulo of at least 2".into()),
+        Some(true) if matches!(wall_type, WallType::LeftConst) => Err("composite cannot be used with the left const wall type".into()),
+        Some(composite) => Ok(composite),
+        None => Err("composite is not a bool".into())
+    }
 }
 
+// `"field": {"prime": p, "polynomial": [c_0, c_1, ...]}` computes the wall in GF(p^k) for an irreducible polynomial of degree k,
+// whose terms are field elements written as integers
+fn get_field(input: &JsonValue, wall_type: &WallType) -> Result<Option<GaloisField>, String> {
+    let field = &input["field"];
+    if field.is_null() {
+        return Ok(None);
+    }
+    if !field.is_object() {
+        return Err("field is not an object".into());
+    }
+    for option in ["modulo", "big_integers", "composite", "transforms"] {
+        if !input[option].is_null() {
+            return Err(format!("{option} cannot be used together with field"));
+        }
+    }
+    if matches!(wall_type, WallType::LeftConst) {
+        return Err("field cannot be used with the left const wall type".into());
+    }
+    if input["sequence"]["type"] == "combine" {
+        return Err("field cannot be used with a combined sequence, its operations are not the ones of the field".into());
+    }
 
-fn execute_input(input: &JsonValue) -> Result<(), String> {
-    let wall_type = get_wall_type(input)?;
-    let sequence = get_sequence(input)?;
-    let modulo = get_modulo(input)?;
-    let left_values = get_left_values(input)?;
-    let sequence_start = get_sequence_start(input)?;
-    let colours = get_colours(input)?;
-
-    let boundary = get_boundary(input)?;
+    let prime = field["prime"].as_u64().ok_or(format!("{} is not valid for the field prime, it must be a non negative number", field["prime"]))?;
+    if !field["polynomial"].is_array() {
+        return Err("the field polynomial is not an array".into());
+    }
+    let polynomial = field["polynomial"].members()
+        .map(|c| c.as_i64().map(i128::from).ok_or(format!("{c} is not valid for a coefficient of the field polynomial, it must be an integer")))
+        .collect::<Result<Vec<i128>, String>>()?;
+    Ok(Some(GaloisField::new(prime.into(), &polynomial)?))
+}
 
-    let output_file = get_output_file(input)?;
-    
-    match wall_type {
-        WallType::Repeating => {
-        match sequence {
-        Sequence::Custom(s) => {
-            if boundary.left < 0 {
-                Err("Cannot have a left boundary less than 0".into())
-            } else if boundary.right < 0 {
-                Err("Cannot have a right boundary less than 0".into())
-            } else {
-                run_repeating_wall(s, modulo, boundary, output_file, colours);
-                Ok(())
-            }
-        },
-        _ => Err("Cannot use a pre defined sequence with the repeating wall type".into())
-        }
-        },
-        WallType::LeftConst => {
-            if boundary.left < 0 {
-                Err("Cannot have a left boundary less than 0".into())
-            } else if boundary.right < 0 {
-                Err("Cannot have a right boundary less than 0".into())
-            } else {
-                match sequence {
-                Sequence::Knight => {
-                    run_left_const_fn_wall(left_const_functions::knight, left_values, modulo, boundary, output_file, colours);
-                    Ok(())
-                },
-                Sequence::Rook => {
-                    run_left_const_fn_wall(left_const_functions::rook, left_values, modulo, boundary, output_file, colours);
-                    Ok(())
-                },
-                Sequence::Pagoda => {
-                    run_left_const_fn_wall(left_const_functions::pagoda, left_values, modulo, boundary, output_file, colours);
-                    Ok(())
-                },
-                Sequence::Rueppel => {
-                    run_left_const_fn_wall(left_const_functions::rueppel, left_values, modulo, boundary, output_file, colours);
-                    Ok(())
-                },
-                Sequence::Zigzag => {
-                    run_left_const_fn_wall(left_const_functions::zigzag, left_values, modulo, boundary, output_file, colours);
-                    Ok(())
-                },
-                Sequence::Custom(s) => {
-                    run_left_const_wall(s, left_values, modulo, boundary, output_file, colours);
-                    Ok(())
-                },
+// `"rational": true` computes the wall exactly over the rationals, the terms of a custom sequence can then be fractions like "1/3"
+fn get_rational(input: &JsonValue, modulo: i128, wall_type: &WallType) -> Result<bool, String> {
+    if input["rational"].is_null() {
+        return Ok(false);
+    }
+    match input["rational"].as_bool() {
+        Some(true) if modulo != 0 => Err("rational walls need a modulo of 0".into()),
+        Some(true) if matches!(wall_type, WallType::LeftConst) => Err("rational cannot be used with the left const wall type".into()),
+        Some(true) => {
+            for option in ["field", "composite", "big_integers", "ensemble"] {
+                if !input[option].is_null() {
+                    return Err(format!("{option} cannot be used together with rational"));
                 }
             }
+            Ok(true)
         },
-        WallType::BiDirectional => {
-            match sequence {
-                Sequence::Knight => {
-                    run_bi_directional_fn_wall(bi_directional_functions::knight, modulo, boundary, output_file, colours);
-                    Ok(())
-                },
-                Sequence::Rook => {
-                    run_bi_directional_fn_wall(bi_directional_functions::rook, modulo, boundary, output_file, colours);
-                    Ok(())
-                },
-                Sequence::Pagoda => {
-                    run_bi_directional_fn_wall(bi_directional_functions::pagoda, modulo, boundary, output_file, colours);
-                    Ok(())
-                },
-                Sequence::Rueppel => {
-                    run_bi_directional_fn_wall(bi_directional_functions::rueppel, modulo, boundary, output_file, colours);
-                    Ok(())
-                },
-                Sequence::Zigzag => {
-                    run_bi_directional_fn_wall(bi_directional_functions::zigzag, modulo, boundary, output_file, colours);
-                    Ok(())
-                },
-                Sequence::Custom(s) => {
-                    run_bi_directional_wall(s, sequence_start, modulo, boundary, output_file, colours);
-                    Ok(())
-                },
-            }
-        }
+        Some(false) => Ok(false),
+        None => Err("rational is not a bool".into())
     }
 }
 
-fn run_repeating_wall(sequence: Vec<i128>, modulo: i128, boundary: Boundary, output_file: String, colours: Colours) {
-    let mut holder = repeating_sequence_wall::RepeatingSequenceWallHolder::new(sequence, modulo, boundary.top, boundary.bottom, boundary.left as usize, boundary.right as usize);
-
-    let len:u32 = (boundary.right-boundary.left+1).try_into().unwrap();
-    let height:u32 = (boundary.bottom-boundary.top).try_into().unwrap();
-
-    let mut img = image::RgbImage::new(len, height+1);
-    let mut y = 0;
-
-    while let Some(_) = holder.calculate_next_line() {
-        let line = holder.get_last_line().unwrap();
-        
-        for (x, val) in line.iter().enumerate() {
-            let x = x.try_into().unwrap();
-            img.put_pixel(x, y, get_colour(*val, &colours));
-        }
-        y += 1;
+fn parse_rational_value(value: &JsonValue) -> Result<BigRational, String> {
+    if let Some(text) = value.as_str() {
+        rational::parse_rational(text)
+    } else if let Some(v) = value.as_i64() {
+        Ok(BigRational::from_integer(v.into()))
+    } else {
+        Err("it is not a number".into())
     }
-
-    img.save(output_file).unwrap();
 }
 
-fn run_left_const_fn_wall<F>(sequence_func: F, left_values: [i128; 2], modulo: i128, boundary: Boundary, output_file: String, colours: Colours)
-    where
-        F: Fn(usize) -> i128
-{
-    let mut holder = left_const_wall::LeftConstWallHolder::new_from_sequence_func(sequence_func, left_values, modulo, boundary.top, boundary.bottom, boundary.left as usize, boundary.right as usize);
-
-    let len:u32 = (boundary.right-boundary.left+1).try_into().unwrap();
-    let height:u32 = (boundary.bottom-boundary.top).try_into().unwrap();
+fn get_custom_values<T>(values: &JsonValue, name: &str, parse: &dyn Fn(&JsonValue) -> Result<T, String>) -> Result<Vec<T>, String> {
+    if !values.is_array() {
+        return Err(format!("{name} is not an array"));
+    }
+    values.members().enumerate()
+        .map(|(pos, val)| parse(val).map_err(|e| format!("value at position {pos} in {name}: {e}")))
+        .collect()
+}
 
-    let mut img = image::RgbImage::new(len, height+1);
-    let mut y = 0;
+// The terms of a custom sequence that are not i128s and the index of the first, like `get_two_sided_custom`
+fn get_custom_terms<T>(sequence: &JsonValue, parse: &dyn Fn(&JsonValue) -> Result<T, String>) -> Result<(Vec<T>, isize), String> {
+    if sequence.is_array() {
+        return Ok((get_custom_values(sequence, "sequence", parse)?, DEFAULT_SEQUENCE_START));
+    }
+    let mut values = if sequence["negative"].is_null() {
+        vec![]
+    } else {
+        get_custom_values(&sequence["negative"], "negative", parse)?
+    };
+    let offset = -(values.len() as isize);
+    if !sequence["positive"].is_null() {
+        values.extend(get_custom_values(&sequence["positive"], "positive", parse)?);
+    }
+    if values.is_empty() {
+        return Err("custom sequence does not have any negative or positive values".into());
+    }
+    Ok((values, offset))
+}
 
-    while let Some(_) = holder.calculate_next_line() {
-        let line = holder.get_last_line().unwrap();
-        
-        for (x, val) in line.iter().enumerate() {
-            let x = x.try_into().unwrap();
-            img.put_pixel(x, y, get_colour(*val, &colours));
+// `"symbolic": {"colour_by": "degree" or "zero", "degree_colour": colour, "table_file": path, "table_format": "text" or "latex"}` computes the wall
+// of a custom sequence whose terms are integers or symbols like "a0", so every cell is a polynomial in the symbols.
+// The cells of the highest degree get the degree colour.
+fn get_symbolic(input: &JsonValue, modulo: i128, wall_type: &WallType) -> Result<Option<SymbolicSettings>, String> {
+    let symbolic = &input["symbolic"];
+    if symbolic.is_null() {
+        return Ok(None);
+    }
+    if !symbolic.is_object() {
+        return Err("symbolic is not an object".into());
+    }
+    if modulo != 0 {
+        return Err("symbolic walls need a modulo of 0".into());
+    }
+    if matches!(wall_type, WallType::LeftConst) {
+        return Err("symbolic cannot be used with the left const wall type".into());
+    }
+    if !(input["sequence"].is_array() || input["sequence"]["type"] == "custom") {
+        return Err("symbolic needs a custom sequence, whose terms are integers or symbols".into());
+    }
+    for option in ["rational", "field", "composite", "big_integers", "ensemble", "qd_table"] {
+        if !input[option].is_null() {
+            return Err(format!("{option} cannot be used together with symbolic"));
         }
-        y += 1;
     }
 
-    img.save(output_file).unwrap();
-}
-
-fn run_left_const_wall(sequence: Vec<i128>, left_values: [i128; 2], modulo: i128, boundary: Boundary, output_file: String, colours: Colours) {
-    let mut holder = left_const_wall::LeftConstWallHolder::new(sequence, left_values, modulo, boundary.top, boundary.bottom, boundary.left as usize, boundary.right as usize);
+    let colouring = match symbolic["colour_by"].as_str().map(|c| c.to_ascii_lowercase()).as_deref() {
+        None | Some("degree") => SymbolicColouring::Degree,
+        Some("zero") => SymbolicColouring::Zero,
+        Some(c) => { return Err(format!("{c} is not a valid symbolic colour_by, it must be degree or zero")); }
+    };
+    let degree_colour = if symbolic["degree_colour"].is_null() { DEFAULT_SYMBOLIC_DEGREE_COLOUR } else { colour_to_u8_array(&symbolic["degree_colour"])? };
+    let table_file = if symbolic["table_file"].is_null() {
+        None
+    } else {
+        Some(symbolic["table_file"].as_str().ok_or("symbolic table_file is not a string")?.to_owned())
+    };
+    let table_format = match symbolic["table_format"].as_str().map(|f| f.to_ascii_lowercase()).as_deref() {
+        None | Some("text") => TableFormat::Text,
+        Some("latex") => TableFormat::Latex,
+        Some(f) => { return Err(format!("{f} is not a valid symbolic table_format, it must be text or latex")); }
+    };
+    Ok(Some(SymbolicSettings { colouring, degree_colour, table_file, table_format }))
+}
 
-    let len:u32 = (boundary.right-boundary.left+1).try_into().unwrap();
-    let height:u32 = (boundary.bottom-boundary.top).try_into().unwrap();
+// `"qd_table": {"layout": "qd" or "pade", "depth": d, "output_file": csv, "image_file": png}` writes the qd or Padé table
+// of the terms in the columns of a rational wall, to `depth` q and e rows or denominator degrees
+fn get_qd_table(input: &JsonValue, rational: bool) -> Result<Option<QdTableSettings>, String> {
+    let qd_table = &input["qd_table"];
+    if qd_table.is_null() {
+        return Ok(None);
+    }
+    if !rational {
+        return Err("qd_table needs a rational wall".into());
+    }
+    if !qd_table.is_object() {
+        return Err("qd_table is not an object".into());
+    }
+    let layout = match qd_table["layout"].as_str().map(|l| l.to_ascii_lowercase()).as_deref() {
+        None | Some("qd") => TableLayout::Qd,
+        Some("pade") => TableLayout::Pade,
+        Some(l) => { return Err(format!("{l} is not a valid qd_table layout, it must be qd or pade")); }
+    };
+    let depth = if qd_table["depth"].is_null() {
+        DEFAULT_QD_DEPTH
+    } else {
+        qd_table["depth"].as_usize().ok_or(format!("{} is not valid for the qd_table depth, it must be a non negative number", qd_table["depth"]))?
+    };
+    let file = |name: &str| if qd_table[name].is_null() {
+        Ok(None)
+    } else {
+        qd_table[name].as_str().map(|f| Some(f.to_owned())).ok_or(format!("qd_table {name} is not a string"))
+    };
+    let (output_file, image_file) = (file("output_file")?, file("image_file")?);
+    if output_file.is_none() && image_file.is_none() {
+        return Err("qd_table needs an output_file or an image_file".into());
+    }
+    Ok(Some(QdTableSettings { layout, depth, output_file, image_file }))
+}
 
-    let mut img = image::RgbImage::new(len, height+1);
-    let mut y = 0;
+fn get_verify_settings(input: &JsonValue) -> Result<VerifySettings, String> {
+    let verify = &input["verify"];
+    if !verify.is_null() && !verify.is_object() {
+        return Err("verify is not an object".into());
+    }
+    let samples = if verify["samples"].is_null() {
+        None
+    } else {
+        Some(verify["samples"].as_usize().ok_or(format!("{} is not valid for the verify samples, it must be a non negative number", verify["samples"]))?)
+    };
+    let seed = if verify["seed"].is_null() {
+        DEFAULT_VERIFY_SEED
+    } else {
+        verify["seed"].as_u64().ok_or(format!("{} is not valid for the verify seed, it must be a non negative number", verify["seed"]))?
+    };
+    Ok(VerifySettings { samples, seed })
+}
+
+fn get_check_frames(input: &JsonValue, modulo: i128, rational: bool, composite: bool) -> Result<bool, String> {
+    if input["check_frames"].is_null() {
+        return Ok(false);
+    }
+    match input["check_frames"].as_bool() {
+        Some(true) if rational => Err("check_frames cannot be used with a rational wall".into()),
+        Some(true) if composite || (modulo > 1 && !composite::is_prime(modulo)) => Err(format!("the frame theorems only hold over the integers or mod a prime, not mod {modulo}")),
+        Some(check) => Ok(check),
+        None => Err("check_frames is not a bool".into())
+    }
+}
+
+fn get_zero_windows(input: &JsonValue, rational: bool) -> Result<Option<ZeroWindowSettings>, String> {
+    let zero_windows = &input["zero_windows"];
+    if zero_windows.is_null() {
+        return Ok(None);
+    }
+    if !zero_windows.is_object() {
+        return Err("zero_windows is not an object".into());
+    }
+    if rational {
+        return Err("zero_windows cannot be used with a rational wall".into());
+    }
+    let output_file = zero_windows["output_file"].as_str().ok_or("zero_windows output_file is not a string")?.to_owned();
+    // the format is taken from the file extension if it is not given
+    let format = match zero_windows["format"].as_str().map(|f| f.to_ascii_lowercase()).as_deref() {
+        None if output_file.to_ascii_lowercase().ends_with(".csv") => WindowFileFormat::Csv,
+        None | Some("json") => WindowFileFormat::Json,
+        Some("csv") => WindowFileFormat::Csv,
+        Some(f) => { return Err(format!("{f} is not a valid zero_windows format, it must be json or csv")); }
+    };
+    Ok(Some(ZeroWindowSettings { output_file, format }))
+}
+
+fn get_frame_overlay(input: &JsonValue, rational: bool) -> Result<Option<FrameOverlay>, String> {
+    let overlay = &input["frame_overlay"];
+    if overlay.is_null() {
+        return Ok(None);
+    }
+    if !overlay.is_object() {
+        return Err("frame_overlay is not an object".into());
+    }
+    let colour_or = |name: &str, default: [u8; 3]| if overlay[name].is_null() { Ok(default) } else { colour_to_u8_array(&overlay[name]) };
+    let colours = FrameColours {
+        inner: colour_or("inner", DEFAULT_INNER_FRAME_COLOUR)?,
+        outer: colour_or("outer", DEFAULT_OUTER_FRAME_COLOUR)?,
+        outline: colour_or("outline", DEFAULT_WINDOW_OUTLINE_COLOUR)?
+    };
+    let svg_file = if overlay["svg_file"].is_null() {
+        None
+    } else {
+        Some(overlay["svg_file"].as_str().ok_or("frame_overlay svg_file is not a string")?.to_owned())
+    };
+    if svg_file.is_some() && rational {
+        return Err("frame_overlay svg_file cannot be used with a rational wall".into());
+    }
+    let ratios = match (overlay["ratios"].is_null(), overlay["ratios"].as_bool()) {
+        (true, _) => false,
+        (false, Some(ratios)) => ratios,
+        (false, None) => { return Err("frame_overlay ratios is not a bool".into()); }
+    };
+    if ratios && svg_file.is_none() {
+        return Err("frame_overlay ratios are only written in svg output, it needs an svg_file".into());
+    }
+    Ok(Some(FrameOverlay { colours, svg_file, ratios }))
+}
+
+fn get_complexity_settings(input: &JsonValue) -> Result<ComplexitySettings, String> {
+    let complexity = &input["complexity"];
+    if !complexity.is_null() && !complexity.is_object() {
+        return Err("complexity is not an object".into());
+    }
+    let file = |name: &str| if complexity[name].is_null() {
+        Ok(None)
+    } else {
+        complexity[name].as_str().map(|f| Some(f.to_owned())).ok_or(format!("complexity {name} is not a string"))
+    };
+    Ok(ComplexitySettings { output_file: file("output_file")?, svg_file: file("svg_file")? })
+}
+
+fn get_randomness_settings(input: &JsonValue) -> Result<RandomnessSettings, String> {
+    let randomness = &input["randomness"];
+    if !randomness.is_null() && !randomness.is_object() {
+        return Err("randomness is not an object".into());
+    }
+    let report_file = if randomness["report_file"].is_null() {
+        DEFAULT_RANDOMNESS_REPORT_FILE.to_owned()
+    } else {
+        randomness["report_file"].as_str().ok_or("randomness report_file is not a string")?.to_owned()
+    };
+    let block_size = if randomness["block_size"].is_null() {
+        DEFAULT_COMPLEXITY_BLOCK_SIZE
+    } else {
+        randomness["block_size"].as_usize().filter(|&b| b > 0).ok_or(format!("{} is not valid for the randomness block_size, it must be a positive number", randomness["block_size"]))?
+    };
+    let significance = if randomness["significance"].is_null() {
+        DEFAULT_SIGNIFICANCE
+    } else {
+        randomness["significance"].as_f64().filter(|s| (0.0..1.0).contains(s)).ok_or(format!("{} is not valid for the randomness significance, it must be from 0 to 1", randomness["significance"]))?
+    };
+    Ok(RandomnessSettings { report_file, block_size, significance })
+}
+
+fn get_overflow_policy(input: &JsonValue) -> Result<OverflowPolicy, String> {
+    if input["overflow"].is_null() {
+        return Ok(OverflowPolicy::Warn);
+    }
+    match input["overflow"].as_str().map(|s| s.to_ascii_lowercase()).as_deref() {
+        Some("fail") => Ok(OverflowPolicy::Fail),
+        Some("warn") => Ok(OverflowPolicy::Warn),
+        Some("ignore") => Ok(OverflowPolicy::Ignore),
+        _ => Err(format!("{} is not a valid overflow, it must be fail, warn or ignore", input["overflow"]))
+    }
+}
+
+fn get_output_file(input: &JsonValue) -> Result<String, String> {
+    if input["output_file"].is_null() {
+        return Ok(DEFAULT_OUTPUT_FILE.into());
+    };
+    if !input["output_file"].is_string() {
+        return Err("output_file is not a string".into());
+    };
+    return Ok( input["output_file"].as_str().unwrap().to_owned() );
+}
+
+fn get_sequence_start(input: &JsonValue, sequence: &Sequence) -> Result<isize, String> {
+    if input["sequence_start"].is_null() {
+        return match sequence {
+            Sequence::Indexed(loaded) => Ok(loaded.offset),
+            _ => Ok(DEFAULT_SEQUENCE_START)
+        };
+    };
+    if !input["sequence_start"].is_number() {
+        return Err("sequence_start is not a number".into());
+    };
+    let ss: Result<isize, String> = input["sequence_start"].as_isize().ok_or("Could not parse sequence_start  as a number".into());
+    return Ok( ss?.to_owned() );
+}
+
+// The start index of custom values only matters for bi directional walls, the other wall types always start at 0
+fn get_sequence_fn(sequence: Sequence, wall_type: &WallType, start: isize, modulo: i128) -> Result<SequenceFn, String> {
+    let start = match wall_type {
+        WallType::BiDirectional => start,
+        _ => 0
+    };
+    Ok(match (sequence, wall_type) {
+        (Sequence::Combined(a, b, combination), _) => {
+            let a_start = file_offset_or(&a, start);
+            let b_start = file_offset_or(&b, start);
+            combine::combine(get_sequence_fn(*a, wall_type, a_start, modulo)?, get_sequence_fn(*b, wall_type, b_start, modulo)?, combination, modulo)
+        },
+        (Sequence::Custom(s), _) => SequenceFn::finite(s, start),
+        (Sequence::Indexed(loaded), _) => SequenceFn::finite(loaded.values, start),
+        (Sequence::Random(r), _) => SequenceFn::two_sided(move |n| r.value(n)),
+        (Sequence::Named(s), WallType::LeftConst) => SequenceFn::one_sided(move |n| s.one_sided(n)),
+        (Sequence::Named(s), _) => SequenceFn::two_sided(move |n| s.two_sided(n)),
+    })
+}
+
+fn file_offset_or(sequence: &Sequence, start: isize) -> isize {
+    match sequence {
+        Sequence::Indexed(loaded) => loaded.offset,
+        _ => start
+    }
+}
+
+fn get_transform_number(transform: &JsonValue, name: &str) -> Result<i64, String> {
+    transform[name].as_i64().ok_or(format!("{} is not valid for the {name} transform, it must be an integer", transform[name]))
+}
+
+fn get_transforms(input: &JsonValue, modulo: i128, wall_type: &WallType) -> Result<Vec<Transform>, String> {
+    let transforms = &input["transforms"];
+    if transforms.is_null() {
+        return Ok(vec![]);
+    };
+    if !transforms.is_array() {
+        return Err("transforms is not an array".into());
+    };
+
+    let mut parsed = vec![];
+    for transform in transforms.members() {
+        let t = if let Some(name) = transform.as_str() {
+            match name.to_ascii_lowercase().as_str() {
+                "reverse" => Transform::Reverse,
+                "negate" => Transform::Negate,
+                "difference" => Transform::Difference,
+                "partial_sums" => Transform::PartialSums,
+                "binomial" => Transform::Binomial,
+                _ => { return Err(format!("{name} is not a valid transform")); }
+            }
+        } else if transform.is_object() && transform.len() == 1 {
+            let name = transform.entries().next().unwrap().0;
+            match name {
+                "shift" => Transform::Shift(get_transform_number(transform, name)? as isize),
+                "scale" => Transform::Scale(get_transform_number(transform, name)?.into()),
+                "add" => Transform::AddConstant(get_transform_number(transform, name)?.into()),
+                "decimate" => {
+                    let k = get_transform_number(transform, name)?;
+                    if k < 1 {
+                        return Err("the decimate transform must be at least 1".into());
+                    }
+                    Transform::Decimate(k as isize)
+                },
+                "mod" => {
+                    let m = get_transform_number(transform, name)?;
+                    if m < 1 {
+                        return Err("the mod transform must be at least 1".into());
+                    }
+                    Transform::Modulo(m.into())
+                },
+                "interleave" => {
+                    let other = get_sequence(&transform[name], modulo)?;
+                    let start = file_offset_or(&other, DEFAULT_SEQUENCE_START);
+                    Transform::Interleave(get_sequence_fn(other, wall_type, start, modulo)?)
+                },
+                _ => { return Err(format!("{name} is not a valid transform")); }
+            }
+        } else {
+            return Err(format!("Can't parse {transform} as a transform"));
+        };
+        parsed.push(t);
+    }
+    Ok(parsed)
+}
+
+fn get_extension(input: &JsonValue) -> Result<Extension, String> {
+    let extension = &input["extension"];
+    if extension.is_null() {
+        return Ok(Extension::Error);
+    };
+    if extension.is_object() && extension.len() == 1 && !extension["constant"].is_null() {
+        let c = extension["constant"].as_i64().ok_or(format!("{} is not valid for a constant extension, it must be an integer", extension["constant"]))?;
+        return Ok(Extension::Constant(c.into()));
+    };
+    match extension.as_str().map(|e| e.to_ascii_lowercase()).as_deref() {
+        Some("error") => Ok(Extension::Error),
+        Some("zero") => Ok(Extension::Zero),
+        Some("periodic") => Ok(Extension::Periodic),
+        Some("mirror") | Some("palindromic") => Ok(Extension::Mirror),
+        Some("antisymmetric") => Ok(Extension::Antisymmetric),
+        _ => Err(format!("{extension} is not a valid extension"))
+    }
+}
+
+// `"periodic": true` treats custom values as one period, `"periodic": "detect"` finds the period in them
+fn get_periodic(input: &JsonValue) -> Result<Option<Periodic>, String> {
+    let periodic = &input["periodic"];
+    if periodic.is_null() || periodic.as_bool() == Some(false) {
+        Ok(None)
+    } else if periodic.as_bool() == Some(true) {
+        Ok(Some(Periodic::Whole))
+    } else if periodic.as_str() == Some("detect") {
+        Ok(Some(Periodic::Detect))
+    } else {
+        Err(format!("{periodic} is not valid for periodic, it must be true, false or \"detect\""))
+    }
+}
+
+// The window of an infinite sequence to use, `{"start": s, "end": e}` or `{"start": s, "length": n}`
+fn get_sample(input: &JsonValue) -> Result<Option<(isize, isize)>, String> {
+    let sample = &input["sample"];
+    if sample.is_null() {
+        return Ok(None);
+    };
+    if !sample.is_object() {
+        return Err("sample is not an object".into());
+    };
+    let start = if sample["start"].is_null() {
+        0
+    } else {
+        sample["start"].as_isize().ok_or(format!("{} is not valid for the sample start", sample["start"]))?
+    };
+    let end = if !sample["end"].is_null() {
+        sample["end"].as_isize().ok_or(format!("{} is not valid for the sample end", sample["end"]))?
+    } else if !sample["length"].is_null() {
+        let length = sample["length"].as_usize().ok_or(format!("{} is not valid for the sample length", sample["length"]))?;
+        if length == 0 {
+            return Err("the sample length must be at least 1".into());
+        }
+        start + length as isize - 1
+    } else {
+        return Err("sample needs an end or a length".into());
+    };
+    Ok(Some((start, end)))
+}
+
+fn get_ensemble(input: &JsonValue) -> Result<Option<EnsembleSettings>, String> {
+    let ensemble = &input["ensemble"];
+    if ensemble.is_null() {
+        return Ok(None);
+    };
+    if !ensemble.is_object() {
+        return Err("ensemble is not an object".into());
+    };
+    let count = ensemble["count"].as_usize().ok_or(format!("{} is not valid for the ensemble count, it must be a non negative number", ensemble["count"]))?;
+    let report_file = if ensemble["report_file"].is_null() {
+        DEFAULT_ENSEMBLE_REPORT_FILE.to_owned()
+    } else {
+        ensemble["report_file"].as_str().ok_or("ensemble report_file is not a string")?.to_owned()
+    };
+    Ok(Some(EnsembleSettings { count, report_file }))
+}
 
-    while let Some(_) = holder.calculate_next_line() {
-        let line = holder.get_last_line().unwrap();
-        
-        for (x, val) in line.iter().enumerate() {
-            let x = x.try_into().unwrap();
-            img.put_pixel(x, y, get_colour(*val, &colours));
+fn colour_to_u8_array(colour: &JsonValue) -> Result<[u8; 3], String> {
+    if colour.is_array() {
+        if colour.len() != 3 {
+            Err(format!("Colour array {colour} doesn't have 3 elements"))
+        } else {
+            Ok([
+                colour[0].as_u8().ok_or(format!("Could not parse element 0 of {colour} as a u8"))?,
+                colour[1].as_u8().ok_or(format!("Could not parse element 1 of {colour} as a u8"))?,
+                colour[2].as_u8().ok_or(format!("Could not parse element 2 of {colour} as a u8"))?,
+            ])
         }
-        y += 1;
+    } else if colour.is_string() {
+        let colour = colour.as_str().unwrap();
+        if colour.len() == 3 {
+            Ok([
+                0x10 * u8::from_str_radix(&colour[0..=0], 16).ok().ok_or(format!("Could not parse character 0 of {colour} as a base 16 value") )?,
+                0x10 * u8::from_str_radix(&colour[1..=1], 16).ok().ok_or(format!("Could not parse character 1 of {colour} as a base 16 value") )?,
+                0x10 * u8::from_str_radix(&colour[2..=2], 16).ok().ok_or(format!("Could not parse character 2 of {colour} as a base 16 value") )?,
+            ])
+        } else if colour.len() == 4 {
+            Ok([
+                0x10 * u8::from_str_radix(&colour[1..=1], 16).ok().ok_or(format!("Could not parse character 1 of {colour} as a base 16 value"))?,
+                0x10 * u8::from_str_radix(&colour[2..=2], 16).ok().ok_or(format!("Could not parse character 2 of {colour} as a base 16 value"))?,
+                0x10 * u8::from_str_radix(&colour[3..=3], 16).ok().ok_or(format!("Could not parse character 3 of {colour} as a base 16 value"))?,
+            ])
+        } else if colour.len() == 6 {
+            Ok([
+                u8::from_str_radix(&colour[0..=1], 16).ok().ok_or(format!("Could not parse characters 0 and 1 of {colour} as a base 16 value"))?,
+                u8::from_str_radix(&colour[2..=3], 16).ok().ok_or(format!("Could not parse characters 2 and 3 of {colour} as a base 16 value"))?,
+                u8::from_str_radix(&colour[4..=5], 16).ok().ok_or(format!("Could not parse characters 4 and 5 of {colour} as a base 16 value"))?,
+            ])
+        } else if colour.len() == 7 {
+            Ok([
+                u8::from_str_radix(&colour[1..=2], 16).ok().ok_or(format!("Could not parse characters 1 and 2 of {colour} as a base 16 value"))?,
+                u8::from_str_radix(&colour[3..=4], 16).ok().ok_or(format!("Could not parse characters 3 and 4 of {colour} as a base 16 value"))?,
+                u8::from_str_radix(&colour[5..=6], 16).ok().ok_or(format!("Could not parse characters 5 and 6 of {colour} as a base 16 value"))?,
+            ])
+        } else {
+            Err(format!("Colour value {colour} is not of the correct type"))
+        }
+    } else {
+        Err(format!("Can't parse {colour} as a colour"))
     }
+}
 
-    img.save(output_file).unwrap();
+// `"valuation": {"prime": p, "zero": colour, "low": colour, "high": colour, "max": m}` in the colours, only the prime is needed
+fn get_valuation_colours(valuation: &JsonValue) -> Result<ValuationColours, String> {
+    if !valuation.is_object() {
+        return Err("valuation is not an object".into());
+    }
+    let prime = valuation["prime"].as_u64().ok_or(format!("{} is not valid for the valuation prime, it must be a non negative number", valuation["prime"]))?;
+    if !composite::is_prime(prime.into()) {
+        return Err(format!("{prime} is not valid for the valuation prime, it is not prime"));
+    }
+    let colour_or = |name: &str, default: [u8; 3]| if valuation[name].is_null() { Ok(default) } else { colour_to_u8_array(&valuation[name]) };
+    let max = if valuation["max"].is_null() {
+        DEFAULT_VALUATION_MAX
+    } else {
+        valuation["max"].as_u32().filter(|m| *m > 0).ok_or(format!("{} is not valid for the valuation max, it must be a positive number", valuation["max"]))?
+    };
+    Ok(ValuationColours {
+        prime: prime.into(),
+        zero: colour_or("zero", DEFAULT_VALUATION_ZERO_COLOUR)?,
+        low: colour_or("low", DEFAULT_VALUATION_LOW_COLOUR)?,
+        high: colour_or("high", DEFAULT_VALUATION_HIGH_COLOUR)?,
+        max
+    })
+}
+
+fn get_window_size_colours(window_size: &JsonValue) -> Result<WindowSizeColours, String> {
+    if !window_size.is_object() {
+        return Err("window_size is not an object".into());
+    }
+    let colour_or = |name: &str, default: [u8; 3]| if window_size[name].is_null() { Ok(default) } else { colour_to_u8_array(&window_size[name]) };
+    let max = if window_size["max"].is_null() {
+        DEFAULT_WINDOW_SIZE_MAX
+    } else {
+        window_size["max"].as_usize().filter(|m| *m > 0).ok_or(format!("{} is not valid for the window_size max, it must be a positive number", window_size["max"]))?
+    };
+    Ok(WindowSizeColours {
+        low: colour_or("low", DEFAULT_WINDOW_SIZE_LOW_COLOUR)?,
+        high: colour_or("high", DEFAULT_WINDOW_SIZE_HIGH_COLOUR)?,
+        max
+    })
+}
+
+fn get_colours(input: &JsonValue) -> Result<Colours, String> {
+    let colours = &input["colours"];
+    if colours.is_null() {
+        let mut cols: HashMap<i128, [u8; 3]> = HashMap::new();
+        for col in DEFAULT_COLOURS {
+            cols.insert(col.0, col.1);
+        }
+        return Ok(Colours { mapping: cols, default: DEFAULT_DEFAULT_COLOUR, undefined: UndefinedCells::Shown, overflow: None, ill_defined: None, valuation: None, window_size: None, frames: None });
+    };
+    if !colours.is_object() {
+        return Err("colours is not an object".into());
+    };
+    let mut default: [u8; 3] = DEFAULT_DEFAULT_COLOUR;
+    let mut undefined = UndefinedCells::Shown;
+    let mut overflow = None;
+    let mut ill_defined = None;
+    let mut valuation = None;
+    let mut window_size = None;
+    let mut cols: HashMap<i128, [u8; 3]> = HashMap::new();
+    for (value, col) in colours.entries() {
+        if value == "default" {
+            default = colour_to_u8_array(col)?;
+        } else if value == "ill_defined" {
+            ill_defined = Some(colour_to_u8_array(col)?);
+        } else if value == "overflow" {
+            overflow = Some(colour_to_u8_array(col)?);
+        } else if value == "valuation" {
+            valuation = Some(get_valuation_colours(col)?);
+        } else if value == "window_size" {
+            window_size = Some(get_window_size_colours(col)?);
+        } else if value == "undefined" {
+            undefined = if col.as_str().is_some_and(|c| c.eq_ignore_ascii_case("transparent")) {
+                UndefinedCells::Transparent
+            } else {
+                UndefinedCells::Colour(colour_to_u8_array(col)?)
+            };
+        } else {
+            match value.parse() {
+                Ok(v) => {
+                    cols.insert(v, colour_to_u8_array(col)?);
+                },
+                Err(_) => {
+                    return Err(format!("Cannot parse {value} as a number"));
+                }
+            }
+        }
+    }
+
+    Ok( Colours { mapping: cols, default, undefined, overflow, ill_defined, valuation, window_size, frames: None } )
+}
+
+
+
+fn get_colour(value: i128, colours: &Colours) -> image::Rgb<u8> {
+    if let Some(valuation) = &colours.valuation {
+        return image::Rgb(valuation.colour(valuation::valuation(value, valuation.prime)));
+    }
+    image::Rgb(*colours.mapping.get(&value).unwrap_or(&colours.default))
+}
+
+
+// Renders the wall of `input`, or analyses it instead as `mode` says
+fn execute_input(input: &JsonValue, mode: Mode) -> Result<(), String> {
+    let wall_type = get_wall_type(input)?;
+    let modulo = get_modulo(input)?;
+    let rational = get_rational(input, modulo, &wall_type)?;
+    let analysing = !matches!(mode, Mode::Render);
+    if analysing && (rational || !input["symbolic"].is_null()) {
+        return Err(format!("{} only works on walls of integers or integers mod a modulo, not rational or symbolic walls", mode.name()));
+    }
+    if rational && (input["sequence"].is_array() || input["sequence"]["type"] == "custom") {
+        return execute_rational_custom(input, wall_type);
+    }
+    if let Some(settings) = get_symbolic(input, modulo, &wall_type)? {
+        return execute_symbolic(input, wall_type, settings);
+    }
+    if modulo == 0 && !analysing && !rational && input["field"].is_null() && get_big_integers(input, modulo, &wall_type)? {
+        if let Some(terms) = get_big_terms(&input["sequence"])? {
+            return execute_big_custom(input, wall_type, terms);
+        }
+    }
+    let sequence = get_sequence(&input["sequence"], modulo)?;
+    let left_values = get_left_values(input)?;
+    let sequence_start = get_sequence_start(input, &sequence)?;
+    let mut colours = get_colours(input)?;
+    colours.undefined = get_undefined_cells(input, &colours)?;
+
+    let auto_region = get_auto_region(input)?;
+    if auto_region && matches!(wall_type, WallType::Repeating) {
+        return Err("region auto cannot be used with the repeating wall type, every cell of it is defined".into());
+    }
+    let boundary = if auto_region { None } else { Some(get_boundary(input)?) };
+
+    let output_file = get_output_file(input)?;
+    let field = get_field(input, &wall_type)?;
+    if colours.valuation.is_some() && (modulo != 0 || field.is_some()) {
+        return Err("valuation colours need an integer wall, with a modulo of 0".into());
+    }
+    // the analyses work on the i128 walls, so integer walls are not given big integers by default
+    let big_integers = field.is_none() && !rational && !analysing && get_big_integers(input, modulo, &wall_type)?;
+    let qd_table = get_qd_table(input, rational)?;
+    let composite = get_composite(input, modulo, &wall_type)?;
+    if big_integers && !composite && modulo > 0 && !composite::is_prime(modulo) {
+        return Err(format!("{modulo} is not prime, so the divisions in the wall may have no inverse, use composite to compute it mod each prime power factor"));
+    }
+    if analysing && (field.is_some() || composite || input["big_integers"] == true) {
+        return Err(format!("{} works on the walls of the number wall generator, it cannot be used with a field, composite or big integers", mode.name()));
+    }
+    let analyses = WallAnalyses {
+        check_frames: get_check_frames(input, modulo, rational, composite)?,
+        zero_windows: get_zero_windows(input, rational)?,
+        frame_overlay: get_frame_overlay(input, rational)?
+    };
+    colours.frames = analyses.frame_overlay.as_ref().map(|o| o.colours);
+    let overflow_policy = get_overflow_policy(input)?;
+
+    let transforms = get_transforms(input, modulo, &wall_type)?;
+    let extension = get_extension(input)?;
+    let sample = get_sample(input)?;
+    let periodic = get_periodic(input)?;
+    if periodic.is_some() && !matches!(extension, Extension::Error) {
+        return Err("Cannot use periodic together with an extension".into());
+    }
+
+    if let Some(settings) = get_ensemble(input)? {
+        if analysing {
+            return Err(format!("{} cannot be used in ensemble mode", mode.name()));
+        }
+        let boundary = boundary.ok_or("region auto needs a finite sequence, it cannot be used in ensemble mode")?;
+        return match sequence {
+            Sequence::Random(random) if transforms.is_empty() => ensemble::run_ensemble(random, wall_type, left_values, modulo, boundary, settings),
+            _ => Err("ensemble mode needs a random sequence without transforms".into())
+        };
+    }
+
+    let sequence = transforms::apply_transforms(get_sequence_fn(sequence, &wall_type, sequence_start, modulo)?, transforms, modulo)?;
+    let sequence = match periodic {
+        Some(periodic) => make_periodic(sequence, periodic, modulo, &wall_type, boundary.as_ref())?,
+        None => extension::extend(sequence, extension)
+    };
+    let sequence = match sample {
+        Some((start, end)) => sequence.sample(start, end)?,
+        None => sequence
+    };
+    let boundary = match boundary {
+        Some(boundary) => boundary,
+        None => fit_boundary(input, defined_region(&sequence, &wall_type))?
+    };
+
+    check_boundary(&sequence, &wall_type, &boundary)?;
+    let mask = wall_mask(&sequence, &wall_type, &colours);
+    if let Mode::Verify(settings) = mode {
+        let terms = verify_terms(&sequence, &wall_type);
+        let lines = sequence_fn_wall_lines(&sequence, wall_type, left_values, modulo, &boundary)?;
+        let report = verify::verify_wall(&lines, terms, modulo, &boundary, &settings)?;
+        report.print_mismatches();
+        println!("{}", report.summary());
+        return if report.mismatches.is_empty() { Ok(()) } else { Err(format!("{} cells do not match their determinants", report.mismatches.len())) };
+    }
+    if let Mode::Complexity(settings) = mode {
+        return execute_complexity(&sequence, wall_type, left_values, modulo, &boundary, &settings);
+    }
+    if let Mode::Recurrence = mode {
+        return execute_recurrence(&sequence, wall_type, left_values, modulo, &boundary);
+    }
+    if let Mode::Randomness(settings) = mode {
+        let lines = execute_randomness(&sequence, wall_type, left_values, modulo, &boundary, &settings)?;
+        save_wall_image(&lines, &boundary, output_file, &colours, mask);
+        return Ok(());
+    }
+    if rational {
+        let terms = wall_terms::<BigInt>(&sequence, wall_type)?;
+        return render_rational_wall(|n| terms(n).map(BigRational::from_integer), &boundary, output_file, &colours, mask, qd_table);
+    }
+    if let Some(field) = field {
+        let lines = big_wall::big_wall_lines(wall_terms(&sequence, wall_type)?, &field, &boundary)?;
+        analyse_wall(&lines, &boundary, &field, mask, &colours, &analyses, &|a, b| field.div(b, a).map_or("?".into(), |r| r.to_string()))?;
+        save_wall_image(&lines, &boundary, output_file, &colours, mask);
+        return Ok(());
+    }
+    if composite {
+        let lines = composite::composite_wall_lines(wall_terms(&sequence, wall_type)?, modulo, &boundary)?;
+        composite::report_ill_defined(&lines, &boundary, modulo, mask);
+        analyse_wall(&lines, &boundary, &BigArithmetic::new(modulo.into()), mask, &colours, &analyses, &big_ratio(modulo))?;
+        save_wall_image(&lines, &boundary, output_file, &colours, mask);
+        return Ok(());
+    }
+    if big_integers {
+        let arithmetic = BigArithmetic::new(modulo.into());
+        let lines = big_wall::big_wall_lines(wall_terms(&sequence, wall_type)?, &arithmetic, &boundary)?;
+        analyse_wall(&lines, &boundary, &arithmetic, mask, &colours, &analyses, &big_ratio(modulo))?;
+        save_wall_image(&lines, &boundary, output_file, &colours, mask);
+        return Ok(());
+    }
+
+    let lines = sequence_fn_wall_lines(&sequence, wall_type, left_values, modulo, &boundary)?;
+    // left const walls need their left values, which only the generator uses, so they are checked with the cross rule instead
+    let exact = match wall_type {
+        WallType::LeftConst => None,
+        _ if overflow::needs_check(modulo, overflow_policy) =>
+            Some(big_wall::big_wall_lines(wall_terms(&sequence, wall_type)?, &BigArithmetic::new(modulo.into()), &boundary)?),
+        _ => None
+    };
+    if analyses.any() {
+        let big_lines: Vec<Vec<Option<BigInt>>> = lines.iter().map(|line| line.iter().map(|&v| Some(v.into())).collect()).collect();
+        analyse_wall(&big_lines, &boundary, &BigArithmetic::new(modulo.into()), mask, &colours, &analyses, &big_ratio(modulo))?;
+    }
+    if modulo > 1 && !composite::is_prime(modulo) {
+        if let Some((y, x, n)) = composite::find_non_invertible(&lines, boundary.top, modulo) {
+            eprintln!("warning: the cell at row {}, column {} needs a division by {n}, which has no inverse mod {modulo}, so it and the cells below it may be wrong, use composite to compute the wall mod each prime power factor",
+                boundary.top + y, boundary.left + x as isize);
+        }
+    }
+    match overflow::check_overflow(&lines, exact.as_deref(), &boundary, modulo, overflow_policy)? {
+        Some(overflowed) if colours.overflow.is_some() => {
+            let marked: Vec<Vec<(i128, bool)>> = lines.into_iter().zip(overflowed)
+                .map(|(line, o)| line.into_iter().zip(o).collect())
+                .collect();
+            save_wall_image(&marked, &boundary, output_file, &colours, mask);
+        },
+        _ => save_wall_image(&lines, &boundary, output_file, &colours, mask)
+    }
+    Ok(())
 }
 
+// A wall of a custom sequence whose terms are not i128s, like fractions or symbols, so they do not go through the i128 sequence functions
+struct CustomWall<T> {
+    values: Vec<T>,
+    first: isize,
+    repeating: bool,
+    boundary: Boundary,
+    colours: Colours,
+    mask: Option<DefinedRegion>,
+    output_file: String
+}
+
+impl<T: Clone> CustomWall<T> {
+    fn term(&self, n: isize) -> Option<T> {
+        let len = self.values.len() as isize;
+        if self.repeating {
+            return Some(self.values[n.rem_euclid(len) as usize].clone());
+        }
+        (self.first..self.first+len).contains(&n).then(|| self.values[(n-self.first) as usize].clone())
+    }
+}
+
+// `mode` names the kind of wall in errors
+fn get_custom_wall<T>(input: &JsonValue, wall_type: &WallType, mode: &str, terms: LoadedSequence<T>) -> Result<CustomWall<T>, String> {
+    for option in ["transforms", "extension", "periodic", "sample"] {
+        if !input[option].is_null() {
+            return Err(format!("{option} cannot be used with a custom sequence in {mode}"));
+        }
+    }
+    let LoadedSequence { values, offset } = terms;
+    let first = match wall_type {
+        WallType::BiDirectional if !input["sequence_start"].is_null() => input["sequence_start"].as_isize().ok_or("sequence_start is not a number")?,
+        WallType::BiDirectional => offset,
+        _ => 0
+    };
+    // an i128 sequence of the same shape, for the boundary
+    let shape = SequenceFn::finite(vec![0; values.len()], first);
+
+    let mut colours = get_colours(input)?;
+    colours.undefined = get_undefined_cells(input, &colours)?;
+    let boundary = if get_auto_region(input)? {
+        if matches!(wall_type, WallType::Repeating) {
+            return Err("region auto cannot be used with the repeating wall type, every cell of it is defined".into());
+        }
+        fit_boundary(input, defined_region(&shape, wall_type))?
+    } else {
+        get_boundary(input)?
+    };
+    check_boundary(&shape, wall_type, &boundary)?;
+    let mask = wall_mask(&shape, wall_type, &colours);
+    let output_file = get_output_file(input)?;
+
+    Ok(CustomWall { values, first, repeating: matches!(wall_type, WallType::Repeating), boundary, colours, mask, output_file })
+}
 
-fn run_bi_directional_fn_wall<F>(sequence_func: F, modulo: i128, boundary: Boundary, output_file: String, colours: Colours)
+fn execute_rational_custom(input: &JsonValue, wall_type: WallType) -> Result<(), String> {
+    let (values, offset) = get_custom_terms(&input["sequence"], &parse_rational_value)?;
+    let wall = get_custom_wall(input, &wall_type, "a rational wall", LoadedSequence { values, offset })?;
+    let qd_table = get_qd_table(input, true)?;
+    render_rational_wall(|n| wall.term(n), &wall.boundary, wall.output_file.clone(), &wall.colours, wall.mask, qd_table)
+}
+
+// The cells of a symbolic wall are polynomials in the symbols of its terms
+fn execute_symbolic(input: &JsonValue, wall_type: WallType, settings: SymbolicSettings) -> Result<(), String> {
+    let (values, offset) = get_custom_terms(&input["sequence"], &symbolic::parse_term)?;
+    let wall = get_custom_wall(input, &wall_type, "a symbolic wall", LoadedSequence { values, offset })?;
+    let arithmetic = SymbolicArithmetic::new(&wall.values);
+    let lines = big_wall::big_wall_lines(|n| wall.term(n).map(|t| arithmetic.polynomial(&t)), &arithmetic, &wall.boundary)?;
+    if let Some(path) = &settings.table_file {
+        symbolic::write_table(&lines, &wall.boundary, &arithmetic, settings.table_format, path)?;
+    }
+
+    let colours = &wall.colours;
+    let zero = colours.mapping.get(&0).copied().unwrap_or(colours.default);
+    let non_zero = colours.mapping.get(&1).copied().unwrap_or(colours.default);
+    let undefined = colours.ill_defined.unwrap_or(colours.default);
+    let max_degree = lines.iter().flatten().flatten().map(|p| p.degree()).max().unwrap_or(0);
+    // cells are coloured from the colour of 1 at degree 0 to the degree colour at the highest degree in the wall
+    let cells: Vec<Vec<image::Rgb<u8>>> = lines.iter().map(|line| line.iter().map(|cell| image::Rgb(match (cell, settings.colouring) {
+        (None, _) => undefined,
+        (Some(p), _) if p.is_zero() => zero,
+        (Some(_), SymbolicColouring::Zero) => non_zero,
+        (Some(p), SymbolicColouring::Degree) => valuation::gradient(non_zero, settings.degree_colour, p.degree(), max_degree)
+    })).collect()).collect();
+    save_wall_image(&cells, &wall.boundary, wall.output_file, colours, wall.mask);
+    Ok(())
+}
+
+// A big integer wall of custom or file terms that are too large for an i128
+fn execute_big_custom(input: &JsonValue, wall_type: WallType, terms: LoadedSequence<BigInt>) -> Result<(), String> {
+    let mut wall = get_custom_wall(input, &wall_type, "a big integer wall with terms too large for an i128", terms)?;
+    let analyses = WallAnalyses {
+        check_frames: get_check_frames(input, 0, false, false)?,
+        zero_windows: get_zero_windows(input, false)?,
+        frame_overlay: get_frame_overlay(input, false)?
+    };
+    wall.colours.frames = analyses.frame_overlay.as_ref().map(|o| o.colours);
+    let arithmetic = BigArithmetic::new(BigInt::zero());
+    let lines = big_wall::big_wall_lines(|n| wall.term(n), &arithmetic, &wall.boundary)?;
+    analyse_wall(&lines, &wall.boundary, &arithmetic, wall.mask, &wall.colours, &analyses, &big_ratio(0))?;
+    save_wall_image(&lines, &wall.boundary, wall.output_file, &wall.colours, wall.mask);
+    Ok(())
+}
+
+fn render_rational_wall<F>(terms: F, boundary: &Boundary, output_file: String, colours: &Colours, mask: Option<DefinedRegion>, qd_table: Option<QdTableSettings>) -> Result<(), String>
     where
-        F: Fn(isize) -> i128
+        F: Fn(isize) -> Option<BigRational>
 {
-    let mut holder = bi_directional_wall::BiDirectionalWallHolder::new_from_sequence_func(sequence_func, modulo, boundary.top, boundary.bottom, boundary.left, boundary.right);
+    let lines = big_wall::big_wall_lines(&terms, &RationalArithmetic, boundary)?;
+    save_wall_image(&lines, boundary, output_file, colours, mask);
+    if let Some(settings) = qd_table {
+        qd_table::write_table(terms, boundary.left..=boundary.right, &settings, colours)?;
+    }
+    Ok(())
+}
 
-    let len:u32 = (boundary.right-boundary.left+1).try_into().unwrap();
-    let height:u32 = (boundary.bottom-boundary.top).try_into().unwrap();
+// Finds the linear complexity profile of the terms in the columns of the boundary over the rationals or mod a prime,
+// and checks it against the zeros of the wall
+fn execute_complexity(sequence: &SequenceFn, wall_type: WallType, left_values: [i128; 2], modulo: i128, boundary: &Boundary, settings: &ComplexitySettings) -> Result<(), String> {
+    check_field_modulo(modulo, "the linear complexity")?;
+    let terms = verify_terms(sequence, &wall_type);
+    let values = (boundary.left..=boundary.right)
+        .map(|n| terms(n).ok_or(format!("the sequence has no term in column {n}")))
+        .collect::<Result<Vec<i128>, String>>()?;
+    let profile = if modulo == 0 {
+        let values: Vec<BigRational> = values.iter().map(|&v| BigRational::from_integer(v.into())).collect();
+        complexity::berlekamp_massey(&RationalArithmetic, &values)?.profile
+    } else {
+        let values: Vec<BigInt> = values.iter().map(|&v| v.into()).collect();
+        complexity::berlekamp_massey(&BigArithmetic::new(modulo.into()), &values)?.profile
+    };
 
-    let mut img = image::RgbImage::new(len, height+1);
-    let mut y = 0;
+    match &settings.output_file {
+        Some(path) => complexity::write_csv(&profile, path)?,
+        None => {
+            for (n, l) in profile.iter().enumerate() {
+                println!("{} {l}", n + 1);
+            }
+        }
+    }
+    if let Some(path) = &settings.svg_file {
+        complexity::write_svg(&profile, path)?;
+    }
+
+    let zeros = wall_zeros(sequence, wall_type, left_values, modulo, boundary, &terms)?;
+    let (checked, mismatches) = complexity::check_against_wall(&profile, |k| {
+        zeros.get((k + 1).checked_sub(boundary.top)?)?.get(k - 1).copied()
+    });
+    for k in &mismatches {
+        eprintln!("warning: the wall has {} in row {}, column {}, but {k} is {} linear complexity of a prefix",
+            if profile.contains(k) { "a zero" } else { "no zero" }, k + 1, boundary.left + *k as isize - 1, if profile.contains(k) { "the" } else { "not the" });
+    }
+    eprintln!("{checked} sizes checked against the wall, {} disagree", mismatches.len());
+    if !mismatches.is_empty() {
+        return Err(format!("the linear complexity profile disagrees with the wall at {} sizes", mismatches.len()));
+    }
+    Ok(())
+}
+
+fn check_field_modulo(modulo: i128, name: &str) -> Result<(), String> {
+    if modulo == 1 || (modulo > 1 && !composite::is_prime(modulo)) {
+        return Err(format!("{name} needs a field, so the modulo must be 0 or prime, not {modulo}"));
+    }
+    Ok(())
+}
+
+// Which cells of the wall are zero, from big integers over the integers as they could overflow an i128
+fn wall_zeros(sequence: &SequenceFn, wall_type: WallType, left_values: [i128; 2], modulo: i128, boundary: &Boundary, terms: &dyn Fn(isize) -> Option<i128>) -> Result<Vec<Vec<bool>>, String> {
+    if modulo == 0 {
+        let lines = big_wall::big_wall_lines(|n| terms(n).map(BigInt::from), &BigArithmetic::new(BigInt::zero()), boundary)?;
+        Ok(lines.iter().map(|line| line.iter().map(|c| c.as_ref().is_some_and(BigInt::is_zero)).collect()).collect())
+    } else {
+        let lines = sequence_fn_wall_lines(sequence, wall_type, left_values, modulo, boundary)?;
+        Ok(lines.iter().map(|line| line.iter().map(|&c| c == 0).collect()).collect())
+    }
+}
+
+// Finds the linear recurrence that each zero window running to the bottom of the wall shows, over the rationals or mod a prime,
+// and checks it against all of the terms of a finite sequence or the terms in the columns of the boundary
+fn execute_recurrence(sequence: &SequenceFn, wall_type: WallType, left_values: [i128; 2], modulo: i128, boundary: &Boundary) -> Result<(), String> {
+    check_field_modulo(modulo, "finding a recurrence")?;
+    let terms = verify_terms(sequence, &wall_type);
+    // all of the terms of a finite sequence, otherwise the terms that the cells of the wall depend on
+    let depth = boundary.bottom as isize;
+    let checked = match (&wall_type, sequence.first, sequence.last) {
+        (WallType::LeftConst, Some(first), Some(last)) => 0..=last-first,
+        (WallType::BiDirectional, Some(first), Some(last)) => first..=last,
+        (WallType::LeftConst, _, _) => 0..=boundary.right + depth,
+        _ => boundary.left - depth..=boundary.right + depth
+    };
+    // the cells of a left const wall that depend on its left values are left out
+    let left_const = matches!(wall_type, WallType::LeftConst);
+    let zeros = wall_zeros(sequence, wall_type, left_values, modulo, boundary, &terms)?;
+    if zeros.is_empty() {
+        return Err("the wall has no rows".into());
+    }
+    let defined = |row: usize, column: isize| !left_const || column + 2 >= row as isize;
 
-    while let Some(_) = holder.calculate_next_line() {
-        let line = holder.get_last_line().unwrap();
-        
-        for (x, val) in line.iter().enumerate() {
-            let x = x.try_into().unwrap();
-            img.put_pixel(x, y, get_colour(*val, &colours));
+    let mut found = false;
+    let (windows, labels) = zero_windows::label_zero_windows_by(&zeros, boundary, |z| Some(*z), defined);
+    let last_labels = labels.last().unwrap();
+    for (index, window) in windows.iter().enumerate() {
+        // only windows whose own cells reach the last line run to the bottom of the wall
+        if !window.cut_off || window.top <= boundary.top || window.top + window.height != boundary.top + zeros.len() {
+            continue;
+        }
+        let columns: Vec<isize> = last_labels.iter().enumerate()
+            .filter(|(_, l)| **l == Some(index))
+            .map(|(x, _)| boundary.left + x as isize)
+            .collect();
+        let (Some(&left), Some(&right)) = (columns.first(), columns.last()) else {
+            continue;
+        };
+        found = true;
+        println!("the zero window from row {}, columns {left} to {right} runs to the bottom of the wall", window.top);
+        // the cells of row r are determinants of size r-1, so the window starts at the size of the recurrence plus 2
+        let order = window.top - 2;
+        if modulo == 0 {
+            report_recurrence(&RationalArithmetic, |n| terms(n).map(|v| BigRational::from_integer(v.into())), order, left..=right, checked.clone(), "over the rationals")?;
+        } else {
+            report_recurrence(&BigArithmetic::new(modulo.into()), |n| terms(n).map(BigInt::from), order, left..=right, checked.clone(), &format!("mod {modulo}"))?;
         }
-        y += 1;
     }
+    if !found {
+        println!("no zero window runs to the bottom of the wall, so the wall shows no recurrence, try a larger bottom");
+    }
+    Ok(())
+}
 
-    img.save(output_file).unwrap();
+// Renders the wall of a sequence mod a prime and writes a report on how random the sequence looks: the linear complexity profile
+// of the terms of the region, the linear complexity test on all of the terms of a finite sequence, and the sizes of the zero windows.
+// Bits are read mod 2, bytes need a prime above 255 like 257 so that no two of them are the same. Returns the lines of the wall to render.
+fn execute_randomness(sequence: &SequenceFn, wall_type: WallType, left_values: [i128; 2], modulo: i128, boundary: &Boundary, settings: &RandomnessSettings) -> Result<Vec<Vec<i128>>, String> {
+    if modulo < 2 || !composite::is_prime(modulo) {
+        return Err(format!("the randomness report needs a prime modulo, like 2 for bits or 257 for bytes, not {modulo}"));
+    }
+    let terms = verify_terms(sequence, &wall_type);
+    let values = |range: RangeInclusive<isize>| range
+        .map(|n| terms(n).ok_or(format!("the sequence has no term in column {n}")))
+        .collect::<Result<Vec<i128>, String>>();
+    // all of the terms of a finite sequence, otherwise the terms in the columns of the boundary
+    let tested = match (&wall_type, sequence.first, sequence.last) {
+        (WallType::LeftConst, Some(first), Some(last)) => 0..=last-first,
+        (WallType::BiDirectional, Some(first), Some(last)) => first..=last,
+        _ => boundary.left..=boundary.right
+    };
+    let (tested, region_terms) = (values(tested)?, values(boundary.left..=boundary.right)?);
+
+    // the cells of a left const wall that depend on its left values are left out, as are the cells that the terms do not reach
+    let region = defined_region(sequence, &wall_type);
+    let left_const = matches!(wall_type, WallType::LeftConst);
+    let defined = |row: usize, column: isize| region.is_none_or(|r| r.contains(row, column)) && (!left_const || column + 2 >= row as isize);
+    let lines = sequence_fn_wall_lines(sequence, wall_type, left_values, modulo, boundary)?;
+    let windows = zero_windows::find_zero_windows_masked(&lines, boundary, defined);
+    randomness::assess(&tested, &region_terms, &windows, modulo, boundary, defined, settings)?;
+    Ok(lines)
 }
 
-fn run_bi_directional_wall(sequence: Vec<i128>, sequence_start: isize, modulo: i128, boundary: Boundary, output_file: String, colours: Colours) {
-    let mut holder = bi_directional_wall::BiDirectionalWallHolder::new(sequence, sequence_start, modulo, boundary.top, boundary.bottom, boundary.left, boundary.right);
+// Prints the recurrence of the terms under a window of the wall in `columns`, and where it holds of the terms in `checked`
+fn report_recurrence<A, F>(arithmetic: &A, terms: F, order: usize, columns: RangeInclusive<isize>, checked: RangeInclusive<isize>, field: &str) -> Result<(), String>
+    where
+        A: WallArithmetic,
+        A::Value: std::fmt::Display,
+        F: Fn(isize) -> Option<A::Value>
+{
+    // the zero determinants of size order+1 in the columns use the terms from order before the first column to order after the last,
+    // as far as they are known
+    let first = (columns.start() - order as isize).max(*checked.start());
+    let last = (columns.end() + order as isize).min(*checked.end());
+    if last - first < 2*order as isize {
+        println!("the window does not have the {} terms under it that are needed to find its recurrence", 2*order + 1);
+        return Ok(());
+    }
+    let values = (first..=last).map(&terms).collect::<Option<Vec<_>>>().ok_or(format!("the terms from {first} to {last} under the window are not all known"))?;
+    let linear_complexity = complexity::berlekamp_massey(arithmetic, &values)?;
+    let recurrence = Recurrence::from_connection(arithmetic, &linear_complexity.connection);
+    if recurrence.order() != order {
+        eprintln!("warning: the terms under the window have a recurrence of order {}, but the window shows one of order {order}", recurrence.order());
+    }
+    println!("{} {field}", recurrence.format(arithmetic, |a| a.to_string()));
+    println!("the wall shows that it holds for the terms from {first} to {last}");
+
+    // the longest run of terms around the window that the recurrence holds for, and the terms that break it
+    let holds = |n: isize| recurrence.holds_at(arithmetic, &terms, n);
+    let failures: Vec<isize> = checked.clone().filter(|&n| holds(n) == Some(false)).collect();
+    let mut start = first + recurrence.order() as isize;
+    while start > *checked.start() && holds(start - 1) == Some(true) {
+        start -= 1;
+    }
+    let mut end = last;
+    while end < *checked.end() && holds(end + 1) == Some(true) {
+        end += 1;
+    }
+    println!("of the terms from {} to {}, it holds for the terms from {} to {end}", checked.start(), checked.end(), start - recurrence.order() as isize);
+    match failures.first() {
+        Some(n) => println!("it does not hold for {} terms, the first is term {n}", failures.len()),
+        None => println!("it holds for every term")
+    }
+    Ok(())
+}
+
+impl WallAnalyses {
+    fn any(&self) -> bool {
+        self.check_frames || self.zero_windows.is_some() || self.frame_overlay.as_ref().is_some_and(|o| o.svg_file.is_some())
+    }
+}
+
+// Runs the analyses of a wall whose cells are `None` where they could not be found.
+// The ratio b/a of two cells of a frame is written as `ratio(a, b)`.
+fn analyse_wall<A>(lines: &[Vec<Option<A::Value>>], boundary: &Boundary, arithmetic: &A, mask: Option<DefinedRegion>, colours: &Colours, analyses: &WallAnalyses, ratio: &dyn Fn(&A::Value, &A::Value) -> String) -> Result<(), String>
+    where
+        A: WallArithmetic,
+        Option<A::Value>: WallCell
+{
+    let defined = |r: usize, c: isize| mask.is_none_or(|m| m.contains(r, c));
+    if analyses.check_frames {
+        frames::check_frames(lines, boundary, arithmetic, defined).print();
+    }
+    let windows = zero_windows::find_zero_windows_by(lines, boundary, |c| c.as_ref().map(|v| arithmetic.is_zero(v)), defined);
+    if let Some(settings) = &analyses.zero_windows {
+        zero_windows::export_zero_windows(&windows, settings)?;
+    }
+    let Some((overlay, path)) = analyses.frame_overlay.as_ref().and_then(|o| Some((o, o.svg_file.as_ref()?))) else {
+        return Ok(());
+    };
+    let cell = |row: usize, column: isize| lines.get(row.checked_sub(boundary.top)?)?.get(usize::try_from(column - boundary.left).ok()?)?.as_ref();
+    let mut labels = vec![];
+    if overlay.ratios {
+        for window in windows.iter().filter(|w| !w.cut_off) {
+            let Some(sides) = frames::frame_sides(window, 1) else {
+                continue;
+            };
+            // the sides are the top, left, right and bottom, whose ratios are P, Q, S and R
+            let texts = sides.iter().zip(["P", "Q", "S", "R"]).map(|(side, name)| match (cell(side[0].0, side[0].1), cell(side[1].0, side[1].1)) {
+                (Some(a), Some(b)) if !arithmetic.is_zero(a) => format!("{name}={}", ratio(a, b)),
+                _ => format!("{name}=?")
+            }).collect();
+            labels.push((*window, texts));
+        }
+    }
+    frames::write_svg(&wall_colours(lines, boundary, colours, mask), boundary, &windows, overlay.colours.outline, &labels, path)
+}
+
+// The ratio of two cells of a big integer wall, a fraction over the integers
+fn big_ratio(modulo: i128) -> impl Fn(&BigInt, &BigInt) -> String {
+    let arithmetic = BigArithmetic::new(modulo.into());
+    move |a, b| if modulo == 0 {
+        BigRational::new(b.clone(), a.clone()).to_string()
+    } else {
+        arithmetic.div(b, a).map_or("?".into(), |r| r.to_string())
+    }
+}
+
+fn make_periodic(sequence: SequenceFn, periodic: Periodic, modulo: i128, wall_type: &WallType, boundary: Option<&Boundary>) -> Result<SequenceFn, String> {
+    let values = sequence.values().ok_or("periodic needs a finite sequence")?;
+    let first = sequence.first.unwrap();
+    let period = period::one_period(values, modulo, periodic)?;
+    if let Periodic::Detect = periodic {
+        eprintln!("the sequence has a period of {}", period.len());
+    }
+
+    match wall_type {
+        // the repeating wall repeats the whole array, so it is given as many periods as the boundary needs
+        WallType::Repeating => {
+            let right = boundary.map_or(0, |b| b.right);
+            let repeats = right.max(0) as usize / period.len() + 1;
+            Ok(SequenceFn::finite(period.repeat(repeats), first))
+        },
+        _ => Ok(extension::extend(SequenceFn::finite(period, first), Extension::Periodic))
+    }
+}
+
+fn execute_search(input: &JsonValue) -> Result<(), String> {
+    let modulo = get_modulo(input)?;
+    let colours = get_colours(input)?;
+    let settings = oeis_search::get_search_settings(input)?;
+
+    oeis_search::run_search(settings, modulo, colours)
+}
+
+// The cells of the wall that only depend on the terms of a finite sequence, `None` if every cell is defined.
+// A left const wall numbers its columns from the first term and is given the terms left of it by its left values.
+fn defined_region(sequence: &SequenceFn, wall_type: &WallType) -> Option<DefinedRegion> {
+    match (wall_type, sequence.first, sequence.last) {
+        (WallType::BiDirectional, Some(first), Some(last)) => Some(DefinedRegion { first: Some(first), last: Some(last) }),
+        (WallType::LeftConst, Some(first), Some(last)) => Some(DefinedRegion { first: None, last: Some(last-first) }),
+        _ => None
+    }
+}
+
+fn check_boundary(sequence: &SequenceFn, wall_type: &WallType, boundary: &Boundary) -> Result<(), String> {
+    if !matches!(wall_type, WallType::BiDirectional) && (boundary.left < 0 || boundary.right < 0) {
+        return Err("Cannot have a left or right boundary less than 0".into());
+    }
+
+    if let (Some(first), Some(last)) = (sequence.first, sequence.last) {
+        let (first, last) = match wall_type {
+            WallType::BiDirectional => (first, last),
+            _ => (0, last-first)
+        };
+        if boundary.left < first || boundary.right > last {
+            return Err(format!("The boundary columns {} to {} are outside of the sequence, which only has values from {first} to {last}", boundary.left, boundary.right));
+        }
+    }
+    Ok(())
+}
+
+fn wall_mask(sequence: &SequenceFn, wall_type: &WallType, colours: &Colours) -> Option<DefinedRegion> {
+    match colours.undefined {
+        UndefinedCells::Shown => None,
+        _ => defined_region(sequence, wall_type)
+    }
+}
+
+fn sequence_fn_wall_lines(sequence: &SequenceFn, wall_type: WallType, left_values: [i128; 2], modulo: i128, boundary: &Boundary) -> Result<Vec<Vec<i128>>, String> {
+    let lines = match (wall_type, sequence.first, sequence.values()) {
+        (WallType::Repeating, _, Some(values)) => repeating_wall_lines(values, modulo, boundary),
+        (WallType::Repeating, _, None) => { return Err("The repeating wall type needs a finite sequence, use sample to take part of an infinite one".into()); },
+        (WallType::LeftConst, _, Some(values)) => left_const_wall_lines(values, left_values, modulo, boundary),
+        (WallType::LeftConst, Some(first), None) => left_const_fn_wall_lines(|n| sequence.value(first + n as isize), left_values, modulo, boundary),
+        (WallType::LeftConst, None, None) => {
+            if sequence.last.is_some() {
+                return Err("The left const wall type needs a sequence with a first term".into());
+            }
+            left_const_fn_wall_lines(|n| sequence.value(n as isize), left_values, modulo, boundary)
+        },
+        (WallType::BiDirectional, first, Some(values)) => bi_directional_wall_lines(values, first.unwrap(), modulo, boundary),
+        (WallType::BiDirectional, first, None) => {
+            if first.is_some() || sequence.last.is_some() {
+                return Err("The bi directional wall type needs a finite or two sided sequence".into());
+            }
+            bi_directional_fn_wall_lines(|n| sequence.value(n), modulo, boundary)
+        }
+    };
+    sequence_fn::check_terms()?;
+    Ok(lines)
+}
+
+// The terms a wall is verified with, `None` outside of a finite sequence.
+// The terms left of a left const wall come from its left values, so the cells that need them are not checked.
+fn verify_terms<'a>(sequence: &'a SequenceFn, wall_type: &WallType) -> Box<dyn Fn(isize) -> Option<i128> + 'a> {
+    match (wall_type, sequence.values()) {
+        (WallType::Repeating, Some(values)) => {
+            let len = values.len() as isize;
+            Box::new(move |n| Some(values[n.rem_euclid(len) as usize]))
+        },
+        (WallType::LeftConst, _) => {
+            let first = sequence.first.unwrap_or(0);
+            Box::new(move |n| (n >= 0 && sequence.contains(first + n)).then(|| sequence.value(first + n)))
+        },
+        _ => Box::new(|n| sequence.contains(n).then(|| sequence.value(n)))
+    }
+}
+
+// Checks the walls of every built in sequence against their determinants, both left const and bi directional
+fn verify_builtin_sequences() -> Result<(), String> {
+    let boundary = DEFAULT_VERIFY_BOUNDARY;
+    let settings = VerifySettings { samples: None, seed: DEFAULT_VERIFY_SEED };
+    let mut failed = 0;
+    for sequence in sequences::builtin_sequences() {
+        let info = sequence.info();
+        let modulo = info.natural_modulo.unwrap_or(0);
+        let left_const = left_const_fn_wall_lines(|n| sequence.one_sided(n), DEFAULT_LEFT_VALUES, modulo, &boundary);
+        let bi_directional = bi_directional_fn_wall_lines(|n| sequence.two_sided(n), modulo, &boundary);
+        let reports = [
+            ("left const", verify::verify_wall(&left_const, |n| (n >= 0).then(|| sequence.one_sided(n as usize)), modulo, &boundary, &settings)?),
+            ("bi directional", verify::verify_wall(&bi_directional, |n| Some(sequence.two_sided(n)), modulo, &boundary, &settings)?)
+        ];
+        for (name, report) in reports {
+            report.print_mismatches();
+            println!("{:<10} {name:<15} {}", info.name, report.summary());
+            if !report.mismatches.is_empty() {
+                failed += 1;
+            }
+        }
+    }
+    if failed > 0 {
+        return Err(format!("{failed} walls do not match their determinants"));
+    }
+    Ok(())
+}
+
+// The terms of a big integer or field wall, `None` outside of a finite sequence
+fn wall_terms<T: From<i128>>(sequence: &SequenceFn, wall_type: WallType) -> Result<Box<dyn Fn(isize) -> Option<T> + '_>, String> {
+    match (wall_type, sequence.values()) {
+        (WallType::Repeating, Some(values)) => {
+            let len = values.len() as isize;
+            Ok(Box::new(move |n| Some(values[n.rem_euclid(len) as usize].into())))
+        },
+        (WallType::Repeating, None) => Err("The repeating wall type needs a finite sequence, use sample to take part of an infinite one".into()),
+        (WallType::LeftConst, _) => Err("big integers cannot be used with the left const wall type".into()),
+        (WallType::BiDirectional, _) => Ok(Box::new(|n| sequence.contains(n).then(|| sequence.value(n).into())))
+    }
+}
+
+// A value in a wall that can be drawn
+trait WallCell {
+    fn colour(&self, colours: &Colours) -> image::Rgb<u8>;
+
+    // whether the cell is zero, `None` if it is not known
+    fn is_zero(&self) -> Option<bool>;
+}
+
+impl WallCell for i128 {
+    fn colour(&self, colours: &Colours) -> image::Rgb<u8> {
+        get_colour(*self, colours)
+    }
+
+    fn is_zero(&self) -> Option<bool> {
+        Some(*self == 0)
+    }
+}
+
+// a cell of an i128 wall and whether it overflowed
+impl WallCell for (i128, bool) {
+    fn colour(&self, colours: &Colours) -> image::Rgb<u8> {
+        match (self.1, colours.overflow) {
+            (true, Some(colour)) => image::Rgb(colour),
+            _ => get_colour(self.0, colours)
+        }
+    }
+
+    fn is_zero(&self) -> Option<bool> {
+        Some(self.0 == 0)
+    }
+}
+
+// cells of a big integer wall that could not be found use the ill defined colour, values too large for the colour mapping use the default colour
+impl WallCell for Option<BigInt> {
+    fn colour(&self, colours: &Colours) -> image::Rgb<u8> {
+        if let (Some(v), Some(valuation)) = (self, &colours.valuation) {
+            return image::Rgb(valuation.colour(valuation::big_valuation(v, valuation.prime)));
+        }
+        match self.as_ref().and_then(|v| i128::try_from(v).ok()) {
+            Some(v) => get_colour(v, colours),
+            None if self.is_none() => image::Rgb(colours.ill_defined.unwrap_or(colours.default)),
+            None => image::Rgb(colours.default)
+        }
+    }
+
+    fn is_zero(&self) -> Option<bool> {
+        self.as_ref().map(BigInt::is_zero)
+    }
+}
+
+// cells of a rational wall are coloured like those of a big integer wall when they are integers, the others use the default colour
+impl WallCell for Option<BigRational> {
+    fn colour(&self, colours: &Colours) -> image::Rgb<u8> {
+        match self {
+            Some(v) if v.is_integer() => Some(v.to_integer()).colour(colours),
+            Some(_) => image::Rgb(colours.default),
+            None => None::<BigInt>.colour(colours)
+        }
+    }
+
+    fn is_zero(&self) -> Option<bool> {
+        self.as_ref().map(BigRational::is_zero)
+    }
+}
+
+// cells that were already given a colour
+impl WallCell for image::Rgb<u8> {
+    fn colour(&self, _: &Colours) -> image::Rgb<u8> {
+        *self
+    }
+
+    fn is_zero(&self) -> Option<bool> {
+        None
+    }
+}
+
+// cells of a field wall are coloured by their encoding as an integer
+impl WallCell for Option<i128> {
+    fn colour(&self, colours: &Colours) -> image::Rgb<u8> {
+        match self {
+            Some(v) => get_colour(*v, colours),
+            None => image::Rgb(colours.ill_defined.unwrap_or(colours.default))
+        }
+    }
+
+    fn is_zero(&self) -> Option<bool> {
+        self.map(|v| v == 0)
+    }
+}
+
+// The colour of each cell of a wall, `None` for transparent cells.
+// Cells outside of `mask` are drawn as `colours.undefined` says.
+fn wall_colours<T: WallCell>(lines: &[Vec<T>], boundary: &Boundary, colours: &Colours, mask: Option<DefinedRegion>) -> Vec<Vec<Option<[u8; 3]>>> {
+    let defined = |x: usize, y: usize| mask.is_none_or(|m| m.contains(boundary.top + y, boundary.left + x as isize));
+    let (width, height) = (lines.first().map_or(0, |l| l.len()), lines.len());
+
+    // zero cells are coloured by the size of their window if window size colours are given, and frames are painted over the cells
+    let labelled = (colours.window_size.is_some() || colours.frames.is_some())
+        .then(|| zero_windows::label_zero_windows_by(lines, boundary, T::is_zero, |r, c| mask.is_none_or(|m| m.contains(r, c))));
+    let sizes = colours.window_size.zip(labelled.as_ref()).map(|(window_size, (windows, labels))| (window_size, zero_windows::window_sizes(windows, labels)));
+    let frames = colours.frames.zip(labelled.as_ref()).map(|(frames, (windows, _))| frames::frame_paint(windows, boundary, width, height, &frames));
+    let cell_colour = |x: usize, y: usize, val: &T| {
+        if let Some(colour) = frames.as_ref().and_then(|f| f[y][x]) {
+            return colour;
+        }
+        if let Some((window_size, sizes)) = &sizes {
+            if let (Some(true), Some(size)) = (val.is_zero(), sizes[y][x]) {
+                return window_size.colour(size);
+            }
+        }
+        val.colour(colours).0
+    };
+
+    lines.iter().enumerate().map(|(y, line)| line.iter().enumerate().map(|(x, val)| {
+        match colours.undefined {
+            UndefinedCells::Transparent if mask.is_some() && !defined(x, y) => None,
+            UndefinedCells::Colour(c) if !defined(x, y) => Some(c),
+            _ => Some(cell_colour(x, y, val))
+        }
+    }).collect()).collect()
+}
 
+fn save_wall_image<T: WallCell>(lines: &[Vec<T>], boundary: &Boundary, output_file: String, colours: &Colours, mask: Option<DefinedRegion>) {
     let len:u32 = (boundary.right-boundary.left+1).try_into().unwrap();
     let height:u32 = (boundary.bottom-boundary.top).try_into().unwrap();
 
+    let cells = wall_colours(lines, boundary, colours, mask);
+
+    if mask.is_some() && matches!(colours.undefined, UndefinedCells::Transparent) {
+        let mut img = image::RgbaImage::new(len, height+1);
+
+        for (y, line) in cells.iter().enumerate() {
+            for (x, colour) in line.iter().enumerate() {
+                let pixel = match colour {
+                    Some([r, g, b]) => image::Rgba([*r, *g, *b, 255]),
+                    None => image::Rgba([0, 0, 0, 0])
+                };
+                img.put_pixel(x.try_into().unwrap(), y.try_into().unwrap(), pixel);
+            }
+        }
+
+        img.save(output_file).unwrap();
+        return;
+    }
+
     let mut img = image::RgbImage::new(len, height+1);
-    let mut y = 0;
 
-    while let Some(_) = holder.calculate_next_line() {
-        let line = holder.get_last_line().unwrap();
-        
-        for (x, val) in line.iter().enumerate() {
-            let x = x.try_into().unwrap();
-            img.put_pixel(x, y, get_colour(*val, &colours));
+    for (y, line) in cells.iter().enumerate() {
+        for (x, colour) in line.iter().enumerate() {
+            img.put_pixel(x.try_into().unwrap(), y.try_into().unwrap(), image::Rgb(colour.unwrap_or(colours.default)));
         }
-        y += 1;
     }
 
     img.save(output_file).unwrap();
 }
 
+fn repeating_wall_lines(sequence: Vec<i128>, modulo: i128, boundary: &Boundary) -> Vec<Vec<i128>> {
+    let mut holder = repeating_sequence_wall::RepeatingSequenceWallHolder::new(sequence, modulo, boundary.top, boundary.bottom, boundary.left as usize, boundary.right as usize);
+
+    let mut lines = vec![];
+    while holder.calculate_next_line().is_some() {
+        lines.push(holder.get_last_line().unwrap().to_vec());
+    }
+    lines
+}
+
+fn left_const_fn_wall_lines<F>(sequence_func: F, left_values: [i128; 2], modulo: i128, boundary: &Boundary) -> Vec<Vec<i128>>
+    where
+        F: Fn(usize) -> i128
+{
+    let mut holder = left_const_wall::LeftConstWallHolder::new_from_sequence_func(sequence_func, left_values, modulo, boundary.top, boundary.bottom, boundary.left as usize, boundary.right as usize);
+
+    let mut lines = vec![];
+    while holder.calculate_next_line().is_some() {
+        lines.push(holder.get_last_line().unwrap().to_vec());
+    }
+    lines
+}
+
+fn left_const_wall_lines(sequence: Vec<i128>, left_values: [i128; 2], modulo: i128, boundary: &Boundary) -> Vec<Vec<i128>> {
+    let mut holder = left_const_wall::LeftConstWallHolder::new(sequence, left_values, modulo, boundary.top, boundary.bottom, boundary.left as usize, boundary.right as usize);
+
+    let mut lines = vec![];
+    while holder.calculate_next_line().is_some() {
+        lines.push(holder.get_last_line().unwrap().to_vec());
+    }
+    lines
+}
+
+fn bi_directional_fn_wall_lines<F>(sequence_func: F, modulo: i128, boundary: &Boundary) -> Vec<Vec<i128>>
+    where
+        F: Fn(isize) -> i128
+{
+    let mut holder = bi_directional_wall::BiDirectionalWallHolder::new_from_sequence_func(sequence_func, modulo, boundary.top, boundary.bottom, boundary.left, boundary.right);
+
+    let mut lines = vec![];
+    while holder.calculate_next_line().is_some() {
+        lines.push(holder.get_last_line().unwrap().to_vec());
+    }
+    lines
+}
+
+fn bi_directional_wall_lines(sequence: Vec<i128>, sequence_start: isize, modulo: i128, boundary: &Boundary) -> Vec<Vec<i128>> {
+    let mut holder = bi_directional_wall::BiDirectionalWallHolder::new(sequence, sequence_start, modulo, boundary.top, boundary.bottom, boundary.left, boundary.right);
+
+    let mut lines = vec![];
+    while holder.calculate_next_line().is_some() {
+        lines.push(holder.get_last_line().unwrap().to_vec());
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quotes_integers_too_large_for_the_json_parser() {
+        assert_eq!(quote_large_integers(r#"[1, -18446744073709551615, 18446744073709551616]"#), r#"[1, -18446744073709551615, "18446744073709551616"]"#);
+        assert_eq!(quote_large_integers(r#"{"a": "123456789012345678901", "b": -123456789012345678901}"#), r#"{"a": "123456789012345678901", "b": "-123456789012345678901"}"#);
+        assert_eq!(quote_large_integers("[123456789012345678901.5, 123456789012345678901e2]"), "[123456789012345678901.5, 123456789012345678901e2]");
+    }
+
+    #[test]
+    fn keeps_terms_too_large_for_an_i128_as_big_integers() {
+        let small = json::parse(r#"{"type": "custom", "positive": [1, "170141183460469231731687303715884105727"]}"#).unwrap();
+        assert!(get_big_terms(&small).unwrap().is_none());
+        let large = json::parse(&quote_large_integers(r#"{"type": "custom", "positive": [1, 170141183460469231731687303715884105728]}"#)).unwrap();
+        let terms = get_big_terms(&large).unwrap().unwrap();
+        assert_eq!(terms.values, vec![BigInt::from(1), BigInt::from(i128::MAX) + 1]);
+        assert!(get_big_terms(&json::parse(r#""rook""#).unwrap()).unwrap().is_none());
+    }
+
+    #[test]
+    fn reduces_large_sequence_values() {
+        // values that fit in an i64 are reduced with the rest of the wall
+        let values = json::parse(&quote_large_integers("[-1, 123456789012345678901234567890123456789, \"-170141183460469231731687303715884105729\", 1e3]")).unwrap();
+        assert_eq!(get_sequence_values(&values, "values", 1_000_000_007).unwrap(), vec![-1, 741_412_909, 360_183_864, 1000]);
+        assert!(get_sequence_values(&values, "values", 0).is_err());
+    }
+
+    #[test]
+    fn symbolic_walls_have_their_own_degree_colour() {
+        let input = json::parse(r#"{"sequence": ["a", "b"], "symbolic": {}}"#).unwrap();
+        assert_eq!(get_symbolic(&input, 0, &WallType::BiDirectional).unwrap().unwrap().degree_colour, DEFAULT_SYMBOLIC_DEGREE_COLOUR);
+        let input = json::parse(r#"{"sequence": ["a", "b"], "symbolic": {"degree_colour": [0, 128, 0]}}"#).unwrap();
+        assert_eq!(get_symbolic(&input, 0, &WallType::BiDirectional).unwrap().unwrap().degree_colour, [0, 128, 0]);
+    }
+
+    #[test]
+    fn finds_recurrences_in_walls_of_a_field() {
+        let fibonacci = SequenceFn::finite((0..24).scan((0, 1), |s, _| { *s = (s.1, s.0 + s.1); Some(s.0) }).collect(), 0);
+        let boundary = Boundary { top: 0, bottom: 8, left: 0, right: 23 };
+        execute_recurrence(&fibonacci, WallType::BiDirectional, DEFAULT_LEFT_VALUES, 0, &boundary).unwrap();
+        execute_recurrence(&fibonacci, WallType::BiDirectional, DEFAULT_LEFT_VALUES, 5, &boundary).unwrap();
+        assert!(execute_recurrence(&fibonacci, WallType::BiDirectional, DEFAULT_LEFT_VALUES, 4, &boundary).is_err());
+    }
+
+    #[test]
+    fn builtin_walls_match_their_determinants() {
+        let boundary = DEFAULT_VERIFY_BOUNDARY;
+        let settings = VerifySettings { samples: None, seed: DEFAULT_VERIFY_SEED };
+        for sequence in sequences::builtin_sequences() {
+            let info = sequence.info();
+            let modulo = info.natural_modulo.unwrap_or(0);
+            let left_const = left_const_fn_wall_lines(|n| sequence.one_sided(n), DEFAULT_LEFT_VALUES, modulo, &boundary);
+            let report = verify::verify_wall(&left_const, |n| (n >= 0).then(|| sequence.one_sided(n as usize)), modulo, &boundary, &settings).unwrap();
+            assert!(report.mismatches.is_empty(), "the left const wall of {} has mismatches: {}", info.name, report.summary());
+
+            let bi_directional = bi_directional_fn_wall_lines(|n| sequence.two_sided(n), modulo, &boundary);
+            let report = verify::verify_wall(&bi_directional, |n| Some(sequence.two_sided(n)), modulo, &boundary, &settings).unwrap();
+            assert!(report.mismatches.is_empty(), "the bi directional wall of {} has mismatches: {}", info.name, report.summary());
+        }
+    }
+}
@@ -0,0 +1,354 @@
+use std::{fs, path::Path};
+use json::JsonValue;
+
+use crate::{Boundary, Colours};
+use crate::composite::is_prime;
+use crate::finite_wall::{DefinedRegion, UndefinedCells};
+use crate::period::smallest_period;
+use crate::sequence_file::parse_reduced_integer;
+use crate::zero_windows::find_zero_windows_masked;
+
+const DEFAULT_MIN_TERMS: usize = 10;
+const DEFAULT_INFINITE_WINDOW_DEPTH: usize = 4;
+
+pub enum SortKey {
+    ANumber,
+    Terms,
+    Period,
+    WindowCount,
+    LargestWindow,
+    InfiniteWindowDepth
+}
+
+pub struct SearchSettings {
+    pub stripped_file: String,
+    pub min_terms: usize,
+    pub max_terms: Option<usize>,
+
+    // filters, `None` means the property is not checked
+    pub max_window: Option<usize>,
+    pub infinite_window: Option<bool>,
+    pub periodic: Option<bool>,
+    // how many rows at the bottom of the wall have to be all zero for it to count as an infinite window
+    pub infinite_window_depth: usize,
+
+    pub sort_by: SortKey,
+    pub descending: bool,
+    pub limit: Option<usize>,
+
+    pub output_file: Option<String>,
+    pub thumbnail_dir: Option<String>
+}
+
+struct SearchResult {
+    a_number: String,
+    terms: usize,
+    period: Option<usize>,
+    window_count: usize,
+    largest_window: usize,
+    infinite_window_depth: usize,
+    // only kept when thumbnails are wanted
+    values: Option<Vec<i128>>
+}
+
+fn get_optional_usize(input: &JsonValue, name: &str) -> Result<Option<usize>, String> {
+    if input[name].is_null() {
+        Ok(None)
+    } else {
+        input[name].as_usize().map(Some).ok_or(format!("{} is not valid for {name}, it must be a non negative number", input[name]))
+    }
+}
+
+fn get_optional_bool(input: &JsonValue, name: &str) -> Result<Option<bool>, String> {
+    if input[name].is_null() {
+        Ok(None)
+    } else {
+        input[name].as_bool().map(Some).ok_or(format!("{name} is not true or false"))
+    }
+}
+
+fn get_optional_string(input: &JsonValue, name: &str) -> Result<Option<String>, String> {
+    if input[name].is_null() {
+        Ok(None)
+    } else {
+        input[name].as_str().map(|s| Some(s.to_owned())).ok_or(format!("{name} is not a string"))
+    }
+}
+
+pub fn get_search_settings(input: &JsonValue) -> Result<SearchSettings, String> {
+    let stripped_file = input["stripped_file"].as_str().ok_or("stripped_file is not a string")?.to_owned();
+
+    let filters = &input["filters"];
+    if !filters.is_null() && !filters.is_object() {
+        return Err("filters is not an object".into());
+    }
+
+    let sort_by = match input["sort_by"].as_str().map(|s| s.to_ascii_lowercase()).as_deref() {
+        None | Some("a_number") => SortKey::ANumber,
+        Some("terms") => SortKey::Terms,
+        Some("period") => SortKey::Period,
+        Some("window_count") => SortKey::WindowCount,
+        Some("largest_window") => SortKey::LargestWindow,
+        Some("infinite_window_depth") => SortKey::InfiniteWindowDepth,
+        Some(s) => { return Err(format!("{s} is not a valid sort_by value")); }
+    };
+
+    Ok(SearchSettings {
+        stripped_file,
+        min_terms: get_optional_usize(input, "min_terms")?.unwrap_or(DEFAULT_MIN_TERMS),
+        max_terms: get_optional_usize(input, "max_terms")?,
+
+        max_window: get_optional_usize(filters, "max_window")?,
+        infinite_window: get_optional_bool(filters, "infinite_window")?,
+        periodic: get_optional_bool(filters, "periodic")?,
+        infinite_window_depth: get_optional_usize(filters, "infinite_window_depth")?.unwrap_or(DEFAULT_INFINITE_WINDOW_DEPTH),
+
+        sort_by,
+        descending: get_optional_bool(input, "descending")?.unwrap_or(false),
+        limit: get_optional_usize(input, "limit")?,
+
+        output_file: get_optional_string(input, "output_file")?,
+        thumbnail_dir: get_optional_string(input, "thumbnail_dir")?
+    })
+}
+
+// Each line of the stripped file looks like `A000045 ,0,1,1,2,3,5,8,`
+fn parse_stripped_line(line: &str, modulo: i128) -> Option<Result<(String, Vec<i128>), String>> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let (a_number, terms) = line.split_once(char::is_whitespace)?;
+    let mut values = vec![];
+    for term in terms.split(',').map(|t| t.trim()).filter(|t| !t.is_empty()) {
+        match parse_reduced_integer(term, modulo) {
+            Ok(v) => values.push(v),
+            Err(e) => { return Some(Err(format!("{a_number}: {e}"))); }
+        }
+    }
+    Some(Ok((a_number.to_owned(), values)))
+}
+
+// the number of rows at the bottom of the defined triangle that are entirely zero
+fn bottom_zero_rows(lines: &[Vec<i128>], boundary: &Boundary, region: &DefinedRegion) -> usize {
+    let mut depth = 0;
+    for row in (2..=boundary.bottom).rev() {
+        let line = &lines[row-boundary.top];
+        let all_zero = line.iter().enumerate()
+            .filter(|(x, _)| region.contains(row, boundary.left + *x as isize))
+            .all(|(_, v)| *v == 0);
+        if !all_zero {
+            break;
+        }
+        depth += 1;
+    }
+    depth
+}
+
+fn finite_wall(values: Vec<i128>, modulo: i128) -> (Vec<Vec<i128>>, Boundary, DefinedRegion) {
+    let region = DefinedRegion::new(0, values.len());
+    // start from the row of ones so that zeros in the sequence are not cut off by the top of the boundary
+    let bottom = region.bottom().expect("the region of a finite sequence has a bottom");
+    let boundary = Boundary { top: 1, bottom, left: 0, right: values.len() as isize - 1 };
+    let lines = crate::bi_directional_wall_lines(values, 0, modulo, &boundary);
+    (lines, boundary, region)
+}
+
+fn analyse_sequence(a_number: String, values: Vec<i128>, modulo: i128, keep_values: bool) -> SearchResult {
+    let terms = values.len();
+    let period = smallest_period(&values);
+    let kept = if keep_values { Some(values.clone()) } else { None };
+    let (lines, boundary, region) = finite_wall(values, modulo);
+
+    let windows = find_zero_windows_masked(&lines, &boundary, |r, c| region.contains(r, c));
+    let window_count = windows.iter().filter(|w| !w.cut_off).count();
+    let largest_window = windows.iter().map(|w| w.size).max().unwrap_or(0);
+    let infinite_window_depth = bottom_zero_rows(&lines, &boundary, &region);
+
+    SearchResult { a_number, terms, period, window_count, largest_window, infinite_window_depth, values: kept }
+}
+
+fn matches_filters(result: &SearchResult, settings: &SearchSettings) -> bool {
+    if let Some(max) = settings.max_window {
+        if result.largest_window > max {
+            return false;
+        }
+    }
+    if let Some(infinite) = settings.infinite_window {
+        if (result.infinite_window_depth >= settings.infinite_window_depth) != infinite {
+            return false;
+        }
+    }
+    if let Some(periodic) = settings.periodic {
+        if result.period.is_some() != periodic {
+            return false;
+        }
+    }
+    true
+}
+
+fn save_thumbnail(values: Vec<i128>, modulo: i128, path: &Path, colours: &Colours) -> Result<(), String> {
+    let (lines, boundary, region) = finite_wall(values, modulo);
+    let width = lines.first().map_or(0, |l| l.len()) as u32;
+    let mut img = image::RgbImage::new(width, lines.len() as u32);
+    for (y, line) in lines.iter().enumerate() {
+        for (x, val) in line.iter().enumerate() {
+            let colour = if region.contains(boundary.top + y, boundary.left + x as isize) {
+                crate::get_colour(*val, colours)
+            } else if let UndefinedCells::Colour(c) = colours.undefined {
+                image::Rgb(c)
+            } else {
+                image::Rgb(colours.default)
+            };
+            img.put_pixel(x as u32, y as u32, colour);
+        }
+    }
+    img.save(path).map_err(|e| format!("Could not save thumbnail {}: {e}", path.display()))
+}
+
+// The walls are i128 walls mod a prime, so that every division of the cross rule is possible and their products fit in an i128
+pub fn run_search(settings: SearchSettings, modulo: i128, colours: Colours) -> Result<(), String> {
+    if modulo > i64::MAX.into() || !is_prime(modulo) {
+        return Err(format!("the search needs a prime modulo below {}, like 2 or 3, not {modulo}", i64::MAX));
+    }
+    let contents = fs::read_to_string(&settings.stripped_file).map_err(|e| format!("Could not read {}: {e}", settings.stripped_file))?;
+
+    let mut results = vec![];
+    let mut skipped = 0;
+    for line in contents.lines() {
+        let (a_number, mut values) = match parse_stripped_line(line, modulo) {
+            None => { continue; },
+            Some(Ok(parsed)) => parsed,
+            Some(Err(e)) => {
+                eprintln!("skipping {e}");
+                skipped += 1;
+                continue;
+            }
+        };
+        if values.len() < settings.min_terms {
+            continue;
+        }
+        if let Some(max) = settings.max_terms {
+            values.truncate(max);
+        }
+
+        let result = analyse_sequence(a_number, values, modulo, settings.thumbnail_dir.is_some());
+        if matches_filters(&result, &settings) {
+            results.push(result);
+        }
+    }
+    if skipped > 0 {
+        eprintln!("{skipped} sequences were skipped");
+    }
+
+    match settings.sort_by {
+        SortKey::ANumber => results.sort_by(|a, b| a.a_number.cmp(&b.a_number)),
+        SortKey::Terms => results.sort_by_key(|r| r.terms),
+        SortKey::Period => results.sort_by_key(|r| r.period.unwrap_or(usize::MAX)),
+        SortKey::WindowCount => results.sort_by_key(|r| r.window_count),
+        SortKey::LargestWindow => results.sort_by_key(|r| r.largest_window),
+        SortKey::InfiniteWindowDepth => results.sort_by_key(|r| r.infinite_window_depth)
+    }
+    if settings.descending {
+        results.reverse();
+    }
+    if let Some(limit) = settings.limit {
+        results.truncate(limit);
+    }
+
+    let mut table = String::from("a_number,terms,period,window_count,largest_window,infinite_window_depth\n");
+    for result in &results {
+        let period = result.period.map_or(String::new(), |p| p.to_string());
+        table += &format!("{},{},{period},{},{},{}\n", result.a_number, result.terms, result.window_count, result.largest_window, result.infinite_window_depth);
+    }
+    match &settings.output_file {
+        Some(file) => fs::write(file, table).map_err(|e| format!("Could not write {file}: {e}"))?,
+        None => print!("{table}")
+    }
+
+    if let Some(dir) = &settings.thumbnail_dir {
+        fs::create_dir_all(dir).map_err(|e| format!("Could not create {dir}: {e}"))?;
+        for result in results {
+            if let Some(values) = result.values {
+                save_thumbnail(values, modulo, &Path::new(dir).join(format!("{}.png", result.a_number)), &colours)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verify::{verify_wall, VerifySettings};
+
+    fn settings(stripped_file: &str, output_file: &str) -> SearchSettings {
+        SearchSettings {
+            stripped_file: stripped_file.to_owned(),
+            min_terms: 4,
+            max_terms: None,
+            max_window: None,
+            infinite_window: None,
+            periodic: None,
+            infinite_window_depth: DEFAULT_INFINITE_WINDOW_DEPTH,
+            sort_by: SortKey::ANumber,
+            descending: false,
+            limit: None,
+            output_file: Some(output_file.to_owned()),
+            thumbnail_dir: None
+        }
+    }
+
+    #[test]
+    fn parses_stripped_lines() {
+        let (a_number, values) = parse_stripped_line("A000045 ,0,1,1,2,3,5,8,13,", 3).unwrap().unwrap();
+        assert_eq!((a_number.as_str(), values), ("A000045", vec![0, 1, 1, 2, 0, 2, 2, 1]));
+        assert!(parse_stripped_line("# comment", 3).is_none());
+        assert!(parse_stripped_line("", 3).is_none());
+        assert!(parse_stripped_line("A000001 ,1,x,", 3).unwrap().is_err());
+    }
+
+    #[test]
+    fn finite_walls_match_their_determinants() {
+        let values = vec![1, 2, 0, 0, 1, 2, 2, 0, 1, 1, 0, 2, 1];
+        let (lines, boundary, region) = finite_wall(values.clone(), 3);
+        let terms = |n: isize| usize::try_from(n).ok().and_then(|i| values.get(i).copied());
+        let report = verify_wall(&lines, terms, 3, &boundary, &VerifySettings { samples: None, seed: 0 }).unwrap();
+        assert!(report.mismatches.is_empty(), "{}", report.summary());
+        // the cells that the determinants can't find are the ones outside of the defined triangle
+        let defined = (0..lines.len()).map(|y| (0..values.len()).filter(|&x| region.contains(y + 1, x as isize)).count()).sum::<usize>();
+        assert_eq!(report.checked, defined);
+    }
+
+    #[test]
+    fn finds_periods_windows_and_infinite_windows() {
+        let periodic = analyse_sequence("A1".into(), vec![1, 0, 0, 1, 0, 0, 1, 0, 0, 1, 0, 0], 2, false);
+        assert_eq!(periodic.period, Some(3));
+        assert!(periodic.infinite_window_depth > 0);
+        let windows = analyse_sequence("A2".into(), vec![1, 1, 0, 0, 0, 1, 1, 0, 1, 1, 1, 0, 1, 0, 0, 1], 2, false);
+        assert_eq!(windows.period, None);
+        assert!(windows.largest_window >= 3);
+    }
+
+    #[test]
+    fn rejects_moduli_that_are_not_prime() {
+        let colours = || crate::get_colours(&json::object!{}).unwrap();
+        for modulo in [0, 4, 12, i128::from(i64::MAX) + 2] {
+            assert!(run_search(settings("missing", "missing"), modulo, colours()).is_err(), "{modulo} is not a prime");
+        }
+    }
+
+    #[test]
+    fn filters_the_table() {
+        let dir = std::env::temp_dir();
+        let stripped = dir.join("oeis_search_stripped.txt");
+        let output = dir.join("oeis_search_table.csv");
+        fs::write(&stripped, "# test\nA000002 ,1,0,0,1,0,0,1,0,0,1,\nA000001 ,1,1,0,1,0,1,1,1,0,0,\nA000003 ,1,1,\n").unwrap();
+        let mut settings = settings(&stripped.to_string_lossy(), &output.to_string_lossy());
+        settings.periodic = Some(false);
+        run_search(settings, 2, crate::get_colours(&json::object!{}).unwrap()).unwrap();
+        let table = fs::read_to_string(&output).unwrap();
+        let a_numbers: Vec<&str> = table.lines().skip(1).map(|l| l.split(',').next().unwrap()).collect();
+        assert_eq!(a_numbers, vec!["A000001"]);
+    }
+}
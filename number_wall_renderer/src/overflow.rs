@@ -0,0 +1,148 @@
+use num_bigint::BigInt;
+
+use crate::Boundary;
+
+// What happens when an i128 wall is found to have overflowed
+#[derive(Debug, Clone, Copy)]
+pub enum OverflowPolicy {
+    Fail,
+    Warn,
+    Ignore
+}
+
+// Whether the cell in row `y`, column `x` of `lines` agrees with the cross rule N*S = C^2 - W*E,
+// `None` if checking it overflows an i128 as well
+fn cross_rule_holds(lines: &[Vec<i128>], y: usize, x: usize, modulo: i128) -> Option<bool> {
+    let reduce = |v: i128| if modulo > 0 { v.rem_euclid(modulo) } else { v };
+    let n = reduce(lines[y-2][x]);
+    let (w, c, e) = (reduce(lines[y-1][x-1]), reduce(lines[y-1][x]), reduce(lines[y-1][x+1]));
+    let s = reduce(lines[y][x]);
+
+    let difference = c.checked_mul(c)?.checked_sub(w.checked_mul(e)?)?.checked_sub(n.checked_mul(s)?)?;
+    Some(reduce(difference) == 0)
+}
+
+// The cells of an i128 wall that differ from the same wall computed exactly with big integers, `None` if there are none.
+// Cells that are not defined in the exact wall are not checked.
+pub fn compare_overflow(lines: &[Vec<i128>], exact: &[Vec<Option<BigInt>>], modulo: i128) -> Option<Vec<Vec<bool>>> {
+    let reduce = |v: i128| if modulo > 0 { v.rem_euclid(modulo) } else { v };
+    let overflowed: Vec<Vec<bool>> = lines.iter().zip(exact)
+        .map(|(line, exact_line)| line.iter().zip(exact_line)
+            .map(|(v, e)| e.as_ref().is_some_and(|e| *e != BigInt::from(reduce(*v))))
+            .collect())
+        .collect();
+    overflowed.iter().any(|l| l.contains(&true)).then_some(overflowed)
+}
+
+// The cells of an i128 wall that are wrong because of overflow, `None` if there are none.
+// This is for walls that can't be computed exactly, like left const walls whose left values only the generator uses,
+// so each cell is checked against the cross rule with checked arithmetic, and every cell computed from a wrong cell is wrong as well.
+// Cells below a zero can't be checked with the cross rule, so they are only marked when a cell above them is.
+pub fn find_overflow(lines: &[Vec<i128>], modulo: i128) -> Option<Vec<Vec<bool>>> {
+    let mut overflowed: Vec<Vec<bool>> = lines.iter().map(|l| vec![false; l.len()]).collect();
+    let mut found = false;
+
+    for y in 1..lines.len() {
+        for x in 0..lines[y].len() {
+            let above = (x.saturating_sub(1)..=(x+1).min(lines[y].len()-1)).any(|i| overflowed[y-1][i]);
+            let wrong = above || (y >= 2 && overflowed[y-2][x]) || {
+                y >= 2 && x >= 1 && x+1 < lines[y].len() && lines[y-2][x] != 0 && cross_rule_holds(lines, y, x, modulo) != Some(true)
+            };
+            if wrong {
+                overflowed[y][x] = true;
+                found = true;
+            }
+        }
+    }
+
+    if found { Some(overflowed) } else { None }
+}
+
+// Whether a wall is checked for overflow, which is integer walls and walls with a modulo whose products don't fit in an i128
+pub fn needs_check(modulo: i128, policy: OverflowPolicy) -> bool {
+    !matches!(policy, OverflowPolicy::Ignore) && (modulo == 0 || modulo > i64::MAX.into())
+}
+
+// Checks an i128 wall against `exact`, the same wall computed with big integers, or the cross rule when there isn't one
+pub fn check_overflow(lines: &[Vec<i128>], exact: Option<&[Vec<Option<BigInt>>]>, boundary: &Boundary, modulo: i128, policy: OverflowPolicy) -> Result<Option<Vec<Vec<bool>>>, String> {
+    if !needs_check(modulo, policy) {
+        return Ok(None);
+    }
+    let found = match exact {
+        Some(exact) => compare_overflow(lines, exact, modulo),
+        None => find_overflow(lines, modulo)
+    };
+    let Some(overflowed) = found else {
+        return Ok(None);
+    };
+
+    let count: usize = overflowed.iter().map(|l| l.iter().filter(|o| **o).count()).sum();
+    let (y, x) = overflowed.iter().enumerate()
+        .find_map(|(y, l)| l.iter().position(|o| *o).map(|x| (y, x)))
+        .unwrap();
+    let message = format!("the wall overflowed an i128 from row {}, column {}, {count} cells are wrong, use big_integers to compute it exactly",
+        boundary.top + y, boundary.left + x as isize);
+
+    match policy {
+        OverflowPolicy::Fail => Err(message),
+        _ => {
+            eprintln!("warning: {message}");
+            Ok(Some(overflowed))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::big_wall::{self, BigArithmetic};
+    use crate::random_sequence::RandomSequence;
+
+    const BOUNDARY: Boundary = Boundary { top: 0, bottom: 5, left: 0, right: 9 };
+
+    fn walls(scale: i128) -> (Vec<Vec<i128>>, Vec<Vec<Option<BigInt>>>) {
+        let random = RandomSequence::new(1, 1_000_000, 1.0);
+        let terms = move |n: isize| random.value(n) * scale;
+        let lines = crate::bi_directional_fn_wall_lines(terms, 0, &BOUNDARY);
+        let exact = big_wall::big_wall_lines(|n| Some(BigInt::from(terms(n))), &BigArithmetic::new(0.into()), &BOUNDARY).unwrap();
+        (lines, exact)
+    }
+
+    #[test]
+    fn finds_the_cells_that_differ_from_the_exact_wall() {
+        let (lines, exact) = walls(1);
+        assert!(compare_overflow(&lines, &exact, 0).is_none());
+        assert!(check_overflow(&lines, Some(&exact), &BOUNDARY, 0, OverflowPolicy::Fail).unwrap().is_none());
+
+        // the 2 by 2 determinants of terms up to 10^14 fit, the 4 by 4 ones are near 10^56
+        let (lines, exact) = walls(100_000_000);
+        let overflowed = compare_overflow(&lines, &exact, 0).unwrap();
+        assert!(overflowed[..4].iter().flatten().all(|o| !o));
+        assert!(overflowed[5].iter().all(|o| *o));
+        assert!(check_overflow(&lines, Some(&exact), &BOUNDARY, 0, OverflowPolicy::Fail).is_err());
+        assert!(check_overflow(&lines, Some(&exact), &BOUNDARY, 0, OverflowPolicy::Warn).unwrap().is_some());
+        assert!(check_overflow(&lines, Some(&exact), &BOUNDARY, 0, OverflowPolicy::Ignore).unwrap().is_none());
+    }
+
+    #[test]
+    fn undefined_exact_cells_are_not_checked() {
+        let lines = vec![vec![0, 0], vec![1, 1], vec![5, 6]];
+        let exact = vec![vec![Some(0.into()), Some(0.into())], vec![Some(1.into()), Some(1.into())], vec![None, Some(7.into())]];
+        let overflowed = compare_overflow(&lines, &exact, 0).unwrap();
+        assert_eq!(overflowed[2], vec![false, true]);
+    }
+
+    #[test]
+    fn the_cross_rule_finds_wrong_cells_and_the_cells_below_them() {
+        let mut lines = vec![vec![0; 5], vec![1; 5], vec![1, 2, 3, 4, 5], vec![-1, -1, -1, -1, -1], vec![0; 5]];
+        // x^2 - w e = 4 - 1*3 = 1 over N = 1 in column 1 is not -1
+        assert!(find_overflow(&lines, 0).is_some());
+        lines[3] = vec![1, 1, 1, 1, 1];
+        lines[4] = vec![0, -2, -2, -2, 0];
+        // 1 - 1 over N = 2, 3 and 4 is wrong, and everything below is marked
+        let overflowed = find_overflow(&lines, 0).unwrap();
+        assert_eq!(overflowed[4], vec![false, true, true, true, false]);
+        assert!(!needs_check(7, OverflowPolicy::Warn));
+        assert!(needs_check(0, OverflowPolicy::Warn));
+    }
+}
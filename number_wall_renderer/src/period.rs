@@ -0,0 +1,46 @@
+// The smallest period that repeats at least twice in `values`, if there is one
+pub fn smallest_period(values: &[i128]) -> Option<usize> {
+    (1..=values.len()/2).find(|&p| (p..values.len()).all(|i| values[i] == values[i-p]))
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Periodic {
+    // the values are exactly one period
+    Whole,
+    // the values are at least two periods, the period is found from them
+    Detect
+}
+
+// One period of `values`, compared mod `modulo` when detecting it
+pub fn one_period(mut values: Vec<i128>, modulo: i128, periodic: Periodic) -> Result<Vec<i128>, String> {
+    if let Periodic::Detect = periodic {
+        let reduced: Vec<i128> = values.iter().map(|v| if modulo > 0 { v.rem_euclid(modulo) } else { *v }).collect();
+        let period = smallest_period(&reduced).ok_or("Could not find a period that repeats at least twice in the sequence")?;
+        values.truncate(period);
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn smallest_periods() {
+        assert_eq!(smallest_period(&[1, 2, 1, 2, 1]), Some(2));
+        assert_eq!(smallest_period(&[5, 5]), Some(1));
+        assert_eq!(smallest_period(&[1, 2, 3, 1, 2, 3]), Some(3));
+        assert_eq!(smallest_period(&[1, 2, 3]), None);
+        assert_eq!(smallest_period(&[1, 1, 2, 1, 1]), None);
+        assert_eq!(smallest_period(&[]), None);
+    }
+
+    #[test]
+    fn one_period_of_the_values() {
+        assert_eq!(one_period(vec![1, 2, 3], 0, Periodic::Whole), Ok(vec![1, 2, 3]));
+        assert_eq!(one_period(vec![1, 2, 1, 2, 1], 0, Periodic::Detect), Ok(vec![1, 2]));
+        // compared mod the modulo, but the values are kept
+        assert_eq!(one_period(vec![1, 4, 1, 4], 3, Periodic::Detect), Ok(vec![1]));
+        assert!(one_period(vec![1, 2, 3], 0, Periodic::Detect).is_err());
+    }
+}
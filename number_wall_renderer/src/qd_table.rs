@@ -0,0 +1,177 @@
+use std::fs;
+use std::ops::RangeInclusive;
+use num_rational::BigRational;
+use num_traits::Zero;
+
+use crate::Colours;
+use crate::determinant;
+use crate::rational::RationalArithmetic;
+
+// Which table is written for a rational wall
+#[derive(Debug, Clone, Copy)]
+pub enum TableLayout {
+    // the q and e columns of the quotient difference algorithm
+    Qd,
+    // the determinants C(L/M) of the Padé table, whose blocks of equal approximants show as squares of zeros
+    Pade
+}
+
+pub struct QdTableSettings {
+    pub layout: TableLayout,
+    pub depth: usize,
+    pub output_file: Option<String>,
+    pub image_file: Option<String>
+}
+
+// Entries that are `None` are not defined
+struct Table {
+    columns: RangeInclusive<isize>,
+    labels: Vec<String>,
+    rows: Vec<Vec<Option<BigRational>>>
+}
+
+fn quotient(num: [&Option<BigRational>; 2], den: [&Option<BigRational>; 2]) -> Option<BigRational> {
+    let (Some(a), Some(b), Some(c), Some(d)) = (num[0], num[1], den[0], den[1]) else {
+        return None;
+    };
+    let den = c * d;
+    if den.is_zero() {
+        return None;
+    }
+    Some(a * b / den)
+}
+
+// q_k^(n) = H_k^(n+1) H_(k-1)^(n) / (H_k^(n) H_(k-1)^(n+1)) and e_k^(n) = H_(k+1)^(n) H_(k-1)^(n+1) / (H_k^(n) H_k^(n+1)),
+// where H_k^(n) is the Hankel determinant of size k from term n
+fn qd_table<F>(sequence: F, columns: RangeInclusive<isize>, depth: usize) -> Result<Table, String>
+    where
+        F: Fn(isize) -> Option<BigRational>
+{
+    let (start, end) = (*columns.start(), *columns.end());
+    let width = columns.clone().count();
+    let mut hankel = vec![];
+    for k in 0..=depth+1 {
+        let row = (start..=end+1).map(|n| determinant::hankel(&RationalArithmetic, &sequence, n, k)).collect::<Result<Vec<_>, _>>()?;
+        hankel.push(row);
+    }
+
+    let (mut labels, mut rows) = (vec![], vec![]);
+    for k in 1..=depth {
+        labels.push(format!("q{k}"));
+        rows.push((0..width).map(|i| quotient([&hankel[k][i+1], &hankel[k-1][i]], [&hankel[k][i], &hankel[k-1][i+1]])).collect());
+        labels.push(format!("e{k}"));
+        rows.push((0..width).map(|i| quotient([&hankel[k+1][i], &hankel[k-1][i+1]], [&hankel[k][i], &hankel[k][i+1]])).collect());
+    }
+    Ok(Table { columns, labels, rows })
+}
+
+// C(L/M) = H_M^(L-M+1), the terms before term 0 are zero as they are for a power series
+fn pade_table<F>(sequence: F, columns: RangeInclusive<isize>, depth: usize) -> Result<Table, String>
+    where
+        F: Fn(isize) -> Option<BigRational>
+{
+    if *columns.end() < 0 {
+        return Err("the Padé table needs columns from 0 on, as they are the degrees of the numerators".into());
+    }
+    let series = |n: isize| if n < 0 { Some(BigRational::zero()) } else { sequence(n) };
+    let columns = (*columns.start()).max(0)..=*columns.end();
+
+    let (mut labels, mut rows) = (vec![], vec![]);
+    for m in 0..=depth {
+        labels.push(format!("M={m}"));
+        let row = columns.clone().map(|l| determinant::hankel(&RationalArithmetic, &series, l - m as isize + 1, m)).collect::<Result<Vec<_>, _>>()?;
+        rows.push(row);
+    }
+    Ok(Table { columns, labels, rows })
+}
+
+fn write_csv(table: &Table, path: &str) -> Result<(), String> {
+    let mut csv: String = table.columns.clone().map(|c| format!(",{c}")).collect();
+    csv.push('\n');
+    for (label, row) in table.labels.iter().zip(&table.rows) {
+        csv.push_str(label);
+        for entry in row {
+            csv.push(',');
+            if let Some(value) = entry {
+                csv.push_str(&value.to_string());
+            }
+        }
+        csv.push('\n');
+    }
+    fs::write(path, csv).map_err(|e| format!("Could not write {path}: {e}"))
+}
+
+// Defined entries use the colours of 0 and 1, the others the ill defined colour
+fn save_table_image(table: &Table, path: &str, colours: &Colours) -> Result<(), String> {
+    let zero = colours.mapping.get(&0).copied().unwrap_or(colours.default);
+    let defined = colours.mapping.get(&1).copied().unwrap_or(colours.default);
+    let undefined = colours.ill_defined.unwrap_or(colours.default);
+
+    let mut img = image::RgbImage::new(table.columns.clone().count() as u32, table.rows.len() as u32);
+    for (y, row) in table.rows.iter().enumerate() {
+        for (x, entry) in row.iter().enumerate() {
+            let colour = match entry {
+                Some(value) if value.is_zero() => zero,
+                Some(_) => defined,
+                None => undefined
+            };
+            img.put_pixel(x as u32, y as u32, image::Rgb(colour));
+        }
+    }
+    img.save(path).map_err(|e| format!("Could not save {path}: {e}"))
+}
+
+// Writes the table of the terms `sequence` over `columns` that `settings` asks for
+pub fn write_table<F>(sequence: F, columns: RangeInclusive<isize>, settings: &QdTableSettings, colours: &Colours) -> Result<(), String>
+    where
+        F: Fn(isize) -> Option<BigRational>
+{
+    let table = match settings.layout {
+        TableLayout::Qd => qd_table(sequence, columns, settings.depth)?,
+        TableLayout::Pade => pade_table(sequence, columns, settings.depth)?
+    };
+    if let Some(path) = &settings.output_file {
+        write_csv(&table, path)?;
+    }
+    if let Some(path) = &settings.image_file {
+        save_table_image(&table, path, colours)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn integers(values: &[i64]) -> Vec<Option<BigRational>> {
+        values.iter().map(|&v| Some(BigRational::from_integer(v.into()))).collect()
+    }
+
+    #[test]
+    fn qd_table_of_a_geometric_sequence() {
+        // 2^n has the quotient 2 and a Hankel rank of 1, so its second q column is not defined
+        let table = qd_table(|n| Some(BigRational::from_integer(2.into()).pow(n as i32)), -2..=2, 2).unwrap();
+        assert_eq!(table.labels, vec!["q1", "e1", "q2", "e2"]);
+        assert_eq!(table.rows[0], integers(&[2; 5]));
+        assert_eq!(table.rows[1], integers(&[0; 5]));
+        assert_eq!(table.rows[2], vec![None; 5]);
+    }
+
+    #[test]
+    fn pade_table_of_a_geometric_series() {
+        // 1/(1-x) is its own approximant for every L, M >= 1 except C(0/2), whose determinant reaches before term 0
+        let table = pade_table(|_| Some(BigRational::from_integer(1.into())), -1..=3, 2).unwrap();
+        assert_eq!(table.columns, 0..=3);
+        assert_eq!(table.labels, vec!["M=0", "M=1", "M=2"]);
+        assert_eq!(table.rows, vec![integers(&[1, 1, 1, 1]), integers(&[1, 1, 1, 1]), integers(&[-1, 0, 0, 0])]);
+        assert!(pade_table(|_| Some(BigRational::zero()), -3..=-1, 1).is_err());
+    }
+
+    #[test]
+    fn writes_csv() {
+        let table = qd_table(|n| Some(BigRational::from_integer((n + 1).into())), 0..=1, 1).unwrap();
+        let path = std::env::temp_dir().join("qd_table_test.csv").to_string_lossy().into_owned();
+        write_csv(&table, &path).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), ",0,1\nq1,2,3/2\ne1,-1/2,-1/6\n");
+    }
+}
@@ -0,0 +1,72 @@
+// A seeded pseudo-random sequence that can be evaluated at any index (including negative ones)
+// without generating the terms before it, so it works with the function based wall holders.
+
+#[derive(Debug, Clone, Copy)]
+pub struct RandomSequence {
+    pub seed: u64,
+    pub alphabet: u64,
+    pub bias: f64
+}
+
+pub fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+impl RandomSequence {
+    pub fn new(seed: u64, alphabet: u64, bias: f64) -> Self {
+        RandomSequence { seed, alphabet, bias }
+    }
+
+    pub fn with_seed(&self, seed: u64) -> Self {
+        RandomSequence { seed, ..*self }
+    }
+
+    // `bias` is the probability of a term being non zero, the non zero symbols are equally likely
+    pub fn value(&self, n: isize) -> i128 {
+        let hash = splitmix64(self.seed ^ splitmix64(n as u64));
+        let unit = (hash >> 11) as f64 / (1u64 << 53) as f64;
+        if self.alphabet < 2 || unit >= self.bias {
+            0
+        } else if self.alphabet == 2 {
+            1
+        } else {
+            1 + (splitmix64(hash) % (self.alphabet - 1)) as i128
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn terms_depend_only_on_the_seed_and_index() {
+        let random = RandomSequence::new(7, 5, 0.5);
+        let forwards: Vec<i128> = (-20..20).map(|n| random.value(n)).collect();
+        let backwards: Vec<i128> = (-20..20).rev().map(|n| random.value(n)).collect();
+        assert_eq!(forwards, backwards.into_iter().rev().collect::<Vec<i128>>());
+        assert_eq!(random.with_seed(7).value(3), random.value(3));
+        assert_ne!((0..64).map(|n| random.value(n)).collect::<Vec<i128>>(), (0..64).map(|n| random.with_seed(8).value(n)).collect::<Vec<i128>>());
+    }
+
+    #[test]
+    fn terms_are_in_the_alphabet() {
+        let random = RandomSequence::new(1, 4, 0.75);
+        let values: Vec<i128> = (-500..500).map(|n| random.value(n)).collect();
+        assert!(values.iter().all(|v| (0..4).contains(v)));
+        for symbol in 0..4 {
+            assert!(values.contains(&symbol), "{symbol} never comes up");
+        }
+    }
+
+    #[test]
+    fn bias_is_the_chance_of_a_non_zero_term() {
+        assert!((0..200).all(|n| RandomSequence::new(3, 2, 0.0).value(n) == 0));
+        assert!((0..200).all(|n| RandomSequence::new(3, 2, 1.0).value(n) == 1));
+        let ones = (0..10000).filter(|&n| RandomSequence::new(3, 2, 0.25).value(n) == 1).count();
+        assert!((2200..2800).contains(&ones), "{ones} of 10000 terms are 1");
+    }
+}
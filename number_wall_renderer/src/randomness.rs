@@ -0,0 +1,337 @@
+use std::fs;
+use json::JsonValue;
+use num_bigint::BigInt;
+
+use crate::Boundary;
+use crate::big_wall::{BigArithmetic, WallArithmetic};
+use crate::complexity::berlekamp_massey;
+use crate::zero_windows::ZeroWindow;
+
+pub struct RandomnessSettings {
+    pub report_file: String,
+    // the number of terms in each block of the linear complexity test
+    pub block_size: usize,
+    // a test fails when its p-value is below this
+    pub significance: f64
+}
+
+// The bounds of T that split the blocks of the linear complexity test into its 7 classes, as in NIST SP 800-22
+const CLASS_BOUNDS: [f64; 6] = [-2.5, -1.5, -0.5, 0.5, 1.5, 2.5];
+const CLASS_NAMES: [&str; 7] = ["T <= -2.5", "-2.5 < T <= -1.5", "-1.5 < T <= -0.5", "-0.5 < T <= 0.5", "0.5 < T <= 1.5", "1.5 < T <= 2.5", "T > 2.5"];
+// NIST asks for at least this many blocks
+const RECOMMENDED_BLOCKS: usize = 200;
+// the smallest expected count of a class of the zero window test
+const MIN_EXPECTED_WINDOWS: f64 = 5.0;
+
+pub struct ChiSquareTest {
+    // the name of each class with the number of times it was seen and the number of times it was expected
+    pub classes: Vec<(String, usize, f64)>,
+    pub degrees_of_freedom: usize,
+    pub chi_square: f64,
+    pub p_value: f64
+}
+
+impl ChiSquareTest {
+    fn new(classes: Vec<(String, usize, f64)>, degrees_of_freedom: usize) -> Self {
+        let chi_square = classes.iter().map(|(_, observed, expected)| (*observed as f64 - expected).powi(2) / expected).sum();
+        let p_value = igamc(degrees_of_freedom as f64 / 2.0, chi_square / 2.0);
+        ChiSquareTest { classes, degrees_of_freedom, chi_square, p_value }
+    }
+
+    pub fn passes(&self, significance: f64) -> bool {
+        self.p_value >= significance
+    }
+
+    fn to_json(&self, significance: f64) -> JsonValue {
+        let classes: Vec<JsonValue> = self.classes.iter().map(|(name, observed, expected)| json::object!{
+            class: name.as_str(),
+            observed: *observed,
+            expected: *expected
+        }).collect();
+        json::object!{
+            classes: classes,
+            degrees_of_freedom: self.degrees_of_freedom,
+            chi_square: self.chi_square,
+            p_value: self.p_value,
+            passed: self.passes(significance)
+        }
+    }
+}
+
+// ln Γ(x) for x > 0, by the Lanczos approximation
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 6] = [76.180_091_729_471_46, -86.505_320_329_416_77, 24.014_098_240_830_91, -1.231_739_572_450_155, 0.001_208_650_973_866_179, -0.000_005_395_239_384_953];
+    let series = COEFFICIENTS.iter().enumerate().fold(1.000_000_000_190_015, |s, (i, c)| s + c / (x + 1.0 + i as f64));
+    (x + 0.5) * (x + 5.5).ln() - (x + 5.5) + (2.506_628_274_631_000_5 * series / x).ln()
+}
+
+// The regularised upper incomplete gamma function Q(a, x), the p-value of a chi square statistic 2x with 2a degrees of freedom
+fn igamc(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 1.0;
+    }
+    let prefix = (a * x.ln() - x - ln_gamma(a)).exp();
+    if x < a + 1.0 {
+        // 1 - P(a, x), with P(a, x) from its series
+        let (mut term, mut sum, mut n) = (1.0 / a, 1.0 / a, a);
+        while term > sum * 1e-15 {
+            n += 1.0;
+            term *= x / n;
+            sum += term;
+        }
+        (1.0 - sum * prefix).max(0.0)
+    } else {
+        // the continued fraction of Q(a, x) by Lentz's method
+        let tiny = 1e-300;
+        let mut b = x + 1.0 - a;
+        let (mut c, mut d) = (1.0 / tiny, 1.0 / b);
+        let mut h = d;
+        for i in 1..1000 {
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.0;
+            d = an * d + b;
+            d = 1.0 / if d.abs() < tiny { tiny } else { d };
+            c = b + an / c;
+            if c.abs() < tiny {
+                c = tiny;
+            }
+            h *= d * c;
+            if (d * c - 1.0).abs() < 1e-15 {
+                break;
+            }
+        }
+        h * prefix
+    }
+}
+
+// The chance that a random sequence of length m over GF(q) has each linear complexity from 0 to m.
+// The number of such sequences with complexity L is 1 for L = 0, (q-1) q^(2L-1) for L <= m/2 and (q-1) q^(2(m-L)) above it.
+fn complexity_probabilities(q: f64, m: usize) -> Vec<f64> {
+    (0..=m).map(|l| if l == 0 {
+        q.powi(-(m as i32))
+    } else if 2*l <= m {
+        (q - 1.0) * q.powi(2*l as i32 - 1 - m as i32)
+    } else {
+        (q - 1.0) * q.powi(m as i32 - 2*l as i32)
+    }).collect()
+}
+
+// The linear complexity test of NIST SP 800-22 over GF(q). The terms are split into blocks of m terms and the complexity L of each block is put into
+// one of 7 classes by T = (-1)^m (L - mu) + 2/9, where mu is the mean complexity of a random block. The chance of each class is found from the chance
+// of each complexity, for q = 2 they are the probabilities given by NIST. Classes that no block can be in are left out.
+pub fn linear_complexity_test<A: WallArithmetic>(arithmetic: &A, q: f64, terms: &[A::Value], m: usize) -> Result<ChiSquareTest, String> {
+    let blocks = terms.len() / m;
+    if blocks == 0 {
+        return Err(format!("the linear complexity test needs at least one block of {m} terms, there are only {} terms", terms.len()));
+    }
+    if blocks < RECOMMENDED_BLOCKS {
+        eprintln!("warning: the linear complexity test has {blocks} blocks, at least {RECOMMENDED_BLOCKS} are needed for its p-value to be reliable");
+    }
+    let probabilities = complexity_probabilities(q, m);
+    let mean: f64 = probabilities.iter().enumerate().map(|(l, p)| l as f64 * p).sum();
+    let class = |l: usize| {
+        let t = (-1f64).powi(m as i32) * (l as f64 - mean) + 2.0 / 9.0;
+        CLASS_BOUNDS.iter().filter(|&&b| t > b).count()
+    };
+
+    let mut chances = [0.0; 7];
+    for (l, p) in probabilities.iter().enumerate() {
+        chances[class(l)] += p;
+    }
+    let mut observed = [0; 7];
+    for block in terms.chunks_exact(m) {
+        let complexity = berlekamp_massey(arithmetic, block)?.profile.last().copied().unwrap_or(0);
+        observed[class(complexity)] += 1;
+    }
+    let classes: Vec<(String, usize, f64)> = (0..7)
+        .filter(|&i| chances[i] > 0.0)
+        .map(|i| (CLASS_NAMES[i].to_owned(), observed[i], chances[i] * blocks as f64))
+        .collect();
+    // the counts add up to the number of blocks, so one class is not free
+    let degrees_of_freedom = classes.len().saturating_sub(1).max(1);
+    Ok(ChiSquareTest::new(classes, degrees_of_freedom))
+}
+
+// The expected number of windows of size g for each cell of the wall of a random sequence over GF(q).
+// A window is q times rarer than one a size smaller, and 1/q of the cells are zeros, the sum of g^2 d_g,
+// so d_g = (q-1)^3 q^(-g-2) / (q+1).
+fn window_density(q: f64, size: usize) -> f64 {
+    (q - 1.0).powi(3) * q.powi(-(size as i32) - 2) / (q + 1.0)
+}
+
+// The number of cells where a window of `size` could have its top left corner without being cut off,
+// so that the window and its inner frame are inside the boundary and defined, and the window is below row 1
+pub fn window_positions<D>(boundary: &Boundary, size: usize, defined: D) -> usize
+    where
+        D: Fn(usize, isize) -> bool
+{
+    let mut positions = 0;
+    for top in (boundary.top + 1).max(2)..=boundary.bottom.saturating_sub(size) {
+        for left in boundary.left + 1..=boundary.right - size as isize {
+            // the defined cells of a wall are convex, so the corners of the frame are enough
+            let (bottom, right) = (top + size, left + size as isize);
+            if defined(top - 1, left - 1) && defined(top - 1, right) && defined(bottom, left - 1) && defined(bottom, right) {
+                positions += 1;
+            }
+        }
+    }
+    positions
+}
+
+// Compares the number of complete windows of each size with the number expected for a random sequence over GF(q).
+// Each size is its own class while at least 5 windows of it or larger are expected, the larger windows are counted together.
+// `None` if fewer than 5 windows are expected at all.
+pub fn window_test<P>(windows: &[ZeroWindow], q: f64, positions: P) -> Option<ChiSquareTest>
+    where
+        P: Fn(usize) -> usize
+{
+    let mut expected = vec![];
+    for size in 1.. {
+        let count = window_density(q, size) * positions(size) as f64;
+        if count < 1e-9 {
+            break;
+        }
+        expected.push(count);
+    }
+    // the number of windows expected of each size and larger
+    let tails: Vec<f64> = expected.iter().rev().scan(0.0, |sum, e| { *sum += e; Some(*sum) }).collect::<Vec<f64>>().into_iter().rev().collect();
+    let last = tails.iter().take_while(|&&t| t >= MIN_EXPECTED_WINDOWS).count();
+    if last == 0 {
+        return None;
+    }
+
+    let complete: Vec<usize> = windows.iter().filter(|w| !w.cut_off).map(|w| w.size).collect();
+    let mut classes: Vec<(String, usize, f64)> = (1..last)
+        .map(|size| (size.to_string(), complete.iter().filter(|&&s| s == size).count(), expected[size - 1]))
+        .collect();
+    classes.push((format!("{last} and larger"), complete.iter().filter(|&&s| s >= last).count(), tails[last - 1]));
+    // the number of windows is not fixed, so every class is free
+    let degrees_of_freedom = classes.len();
+    Some(ChiSquareTest::new(classes, degrees_of_freedom))
+}
+
+// Assesses a sequence mod the prime `modulo` like the output of a random number generator and writes the report.
+// `sequence` is every term that the linear complexity test uses, `region_terms` are the terms in the columns of the boundary,
+// whose linear complexity profile is reported, and `windows` are the windows of the wall, found where `defined` is true.
+pub fn assess<D>(sequence: &[i128], region_terms: &[i128], windows: &[ZeroWindow], modulo: i128, boundary: &Boundary, defined: D, settings: &RandomnessSettings) -> Result<(), String>
+    where
+        D: Fn(usize, isize) -> bool
+{
+    let arithmetic = BigArithmetic::new(modulo.into());
+    let q = modulo as f64;
+    let reduce = |terms: &[i128]| -> Vec<BigInt> { terms.iter().map(|v| v.rem_euclid(modulo).into()).collect() };
+
+    let profile = berlekamp_massey(&arithmetic, &reduce(region_terms))?.profile;
+    // a random sequence keeps its complexity close to half of its length
+    let largest_deviation = profile.iter().enumerate().map(|(n, &l)| (l as f64 - (n + 1) as f64 / 2.0).abs()).fold(0.0, f64::max);
+
+    let complexity_test = linear_complexity_test(&arithmetic, q, &reduce(sequence), settings.block_size)?;
+    let window_test = window_test(windows, q, |size| window_positions(boundary, size, &defined));
+    let passed = complexity_test.passes(settings.significance) && window_test.as_ref().is_none_or(|t| t.passes(settings.significance));
+
+    let mut complexity_report = complexity_test.to_json(settings.significance);
+    complexity_report["block_size"] = settings.block_size.into();
+    complexity_report["blocks"] = (sequence.len() / settings.block_size).into();
+    let report = json::object!{
+        modulo: modulo as u64,
+        terms: sequence.len(),
+        significance: settings.significance,
+        linear_complexity_profile: json::object!{
+            first_column: boundary.left as i64,
+            terms: profile.len(),
+            complexity: profile.last().copied().unwrap_or(0),
+            largest_deviation: largest_deviation,
+            profile: profile.clone()
+        },
+        linear_complexity_test: complexity_report,
+        zero_windows: json::object!{
+            complete: windows.iter().filter(|w| !w.cut_off).count(),
+            cut_off: windows.iter().filter(|w| w.cut_off).count(),
+            test: window_test.as_ref().map_or(JsonValue::Null, |t| t.to_json(settings.significance))
+        },
+        verdict: if passed { "pass" } else { "fail" }
+    };
+    fs::write(&settings.report_file, report.pretty(4)).map_err(|e| format!("Could not write the randomness report to {}: {e}", settings.report_file))?;
+
+    let verdict = |test: &ChiSquareTest| if test.passes(settings.significance) { "pass" } else { "fail" };
+    println!("linear complexity of the {} terms of the region: {}, at most {largest_deviation} from half of the terms", profile.len(), profile.last().copied().unwrap_or(0));
+    println!("linear complexity test: p = {:.6}, {}", complexity_test.p_value, verdict(&complexity_test));
+    match &window_test {
+        Some(test) => println!("zero window test: p = {:.6}, {}", test.p_value, verdict(test)),
+        None => println!("zero window test: too few windows are expected in the region, use a larger one")
+    }
+    println!("verdict: {}", if passed { "pass" } else { "fail" });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random_sequence::RandomSequence;
+    use crate::zero_windows::find_zero_windows;
+
+    #[test]
+    fn p_values() {
+        // Q(1, x) = e^-x and Q(3, x) = e^-x (1 + x + x^2/2)
+        assert!((igamc(1.0, 2.0) - (-2f64).exp()).abs() < 1e-12);
+        assert!((igamc(3.0, 1.0) - 2.5 * (-1f64).exp()).abs() < 1e-12);
+        assert!((igamc(3.0, 20.0) - 221.0 * (-20f64).exp()).abs() < 1e-12);
+        assert_eq!(igamc(2.0, 0.0), 1.0);
+    }
+
+    #[test]
+    fn chances_add_up() {
+        for (q, m) in [(2.0, 10), (2.0, 11), (3.0, 7), (257.0, 4)] {
+            assert!((complexity_probabilities(q, m).iter().sum::<f64>() - 1.0).abs() < 1e-12);
+            // 1/q of the cells of a random wall are zeros
+            let zeros: f64 = (1..200).map(|g| (g * g) as f64 * window_density(q, g)).sum();
+            assert!((zeros - 1.0 / q).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn positions_leave_room_for_the_inner_frame() {
+        let boundary = Boundary { top: 0, bottom: 6, left: 0, right: 5 };
+        assert_eq!(window_positions(&boundary, 1, |_, _| true), 16);
+        assert_eq!(window_positions(&boundary, 4, |_, _| true), 1);
+        assert_eq!(window_positions(&boundary, 5, |_, _| true), 0);
+        assert_eq!(window_positions(&boundary, 1, |r, _| r != 6), 12);
+    }
+
+    #[test]
+    fn random_sequences_pass_and_periodic_ones_fail() {
+        let arithmetic = BigArithmetic::new(2.into());
+        let random = RandomSequence::new(7, 2, 0.5);
+        let terms: Vec<BigInt> = (0..5_000).map(|n| random.value(n).into()).collect();
+        assert!(linear_complexity_test(&arithmetic, 2.0, &terms, 25).unwrap().passes(0.01));
+        let periodic: Vec<BigInt> = (0..5_000).map(|n| BigInt::from(n % 3 / 2)).collect();
+        assert!(!linear_complexity_test(&arithmetic, 2.0, &periodic, 25).unwrap().passes(0.01));
+        assert!(linear_complexity_test(&arithmetic, 2.0, &terms[..10], 25).is_err());
+
+        let boundary = Boundary { top: 0, bottom: 40, left: -60, right: 60 };
+        let windows = find_zero_windows(&crate::bi_directional_fn_wall_lines(|n| random.value(n), 2, &boundary), &boundary);
+        let test = window_test(&windows, 2.0, |size| window_positions(&boundary, size, |_, _| true)).unwrap();
+        assert!(test.passes(0.01), "p = {}", test.p_value);
+        let windows = find_zero_windows(&crate::bi_directional_fn_wall_lines(|n| (n.rem_euclid(3) / 2) as i128, 2, &boundary), &boundary);
+        assert!(!window_test(&windows, 2.0, |size| window_positions(&boundary, size, |_, _| true)).unwrap().passes(0.01));
+        assert!(window_test(&windows, 2.0, |_| 1).is_none());
+    }
+
+    #[test]
+    fn writes_the_report() {
+        let random = RandomSequence::new(3, 2, 0.5);
+        let terms: Vec<i128> = (0..2_000).map(|n| random.value(n)).collect();
+        let boundary = Boundary { top: 0, bottom: 30, left: 0, right: 99 };
+        let windows = find_zero_windows(&crate::bi_directional_fn_wall_lines(|n| random.value(n), 2, &boundary), &boundary);
+        let settings = RandomnessSettings {
+            report_file: std::env::temp_dir().join("randomness_test.json").to_string_lossy().into_owned(),
+            block_size: 20,
+            significance: 0.01
+        };
+        assess(&terms, &terms[..100], &windows, 2, &boundary, |_, _| true, &settings).unwrap();
+        let report = json::parse(&fs::read_to_string(&settings.report_file).unwrap()).unwrap();
+        assert_eq!(report["verdict"], "pass");
+        assert_eq!((report["terms"].as_usize(), report["linear_complexity_test"]["blocks"].as_usize()), (Some(2_000), Some(100)));
+        assert_eq!(report["linear_complexity_profile"]["profile"].len(), 100);
+    }
+}
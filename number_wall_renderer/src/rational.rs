@@ -0,0 +1,84 @@
+use num_rational::BigRational;
+use num_traits::{One, Zero};
+
+use crate::big_wall::WallArithmetic;
+
+// Exact arithmetic over the rationals, for walls of sequences with fractions and for qd tables
+pub struct RationalArithmetic;
+
+impl WallArithmetic for RationalArithmetic {
+    type Value = BigRational;
+
+    fn zero(&self) -> BigRational {
+        BigRational::zero()
+    }
+
+    fn one(&self) -> BigRational {
+        BigRational::one()
+    }
+
+    fn is_zero(&self, value: &BigRational) -> bool {
+        value.is_zero()
+    }
+
+    fn add(&self, a: &BigRational, b: &BigRational) -> BigRational {
+        a + b
+    }
+
+    fn sub(&self, a: &BigRational, b: &BigRational) -> BigRational {
+        a - b
+    }
+
+    fn mul(&self, a: &BigRational, b: &BigRational) -> BigRational {
+        a * b
+    }
+
+    fn div(&self, num: &BigRational, den: &BigRational) -> Result<BigRational, String> {
+        if den.is_zero() {
+            return Err(format!("{num} was divided by zero"));
+        }
+        Ok(num / den)
+    }
+}
+
+// An integer like "-3" or a fraction like "22/7"
+pub fn parse_rational(text: &str) -> Result<BigRational, String> {
+    text.trim().parse().map_err(|e| format!("{text} is not an integer or a fraction: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::big_wall::big_wall_lines;
+    use crate::determinant;
+    use crate::Boundary;
+
+    fn fraction(num: i64, den: i64) -> BigRational {
+        BigRational::new(num.into(), den.into())
+    }
+
+    #[test]
+    fn parses_integers_and_fractions() {
+        assert_eq!(parse_rational(" -3 "), Ok(fraction(-3, 1)));
+        assert_eq!(parse_rational("22/7"), Ok(fraction(22, 7)));
+        assert_eq!(parse_rational("4/6"), Ok(fraction(2, 3)));
+        assert!(parse_rational("1/0").is_err());
+        assert!(parse_rational("0.5").is_err());
+    }
+
+    #[test]
+    fn rational_walls_match_their_determinants() {
+        let boundary = Boundary { top: 0, bottom: 8, left: -6, right: 6 };
+        // zeros every few terms, so there are windows
+        let terms = |n: isize| Some(if n.rem_euclid(5) == 2 { BigRational::zero() } else { fraction(n as i64 % 4, (n.rem_euclid(3) + 1) as i64) });
+        let lines = big_wall_lines(terms, &RationalArithmetic, &boundary).unwrap();
+        for (y, line) in lines.iter().enumerate() {
+            let row = boundary.top + y;
+            for (x, cell) in line.iter().enumerate() {
+                let column = boundary.left + x as isize;
+                let expected = if row == 0 { Some(BigRational::zero()) } else { determinant::toeplitz(&RationalArithmetic, terms, column, row - 1).unwrap() };
+                assert_eq!(cell, &expected, "row {row}, column {column}");
+            }
+        }
+    }
+}
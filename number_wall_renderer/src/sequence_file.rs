@@ -0,0 +1,202 @@
+use std::fs;
+use num_bigint::BigInt;
+
+pub enum SequenceFileFormat {
+    // guess from the contents, a file where every line is `n a(n)` is a b-file
+    Auto,
+    // OEIS b-file, `n a(n)` on each line with `#` comments
+    BFile,
+    // numbers separated by whitespace and/or commas
+    List
+}
+
+pub enum BinaryUnit {
+    // each bit, the most significant bit of each byte first
+    Bit,
+    // each byte, as an integer from 0 to 255
+    Byte
+}
+
+pub struct LoadedSequence<T = i128> {
+    pub values: Vec<T>,
+    // the index of the first value, from the first line of a b-file (0 for lists)
+    pub offset: isize
+}
+
+// Parses a decimal integer of any length
+pub fn parse_big_integer(text: &str) -> Result<BigInt, String> {
+    let text = text.trim();
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(d) => (true, d),
+        None => (false, text.strip_prefix('+').unwrap_or(text))
+    };
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!("{text} is not an integer"));
+    }
+    let value: BigInt = digits.parse().map_err(|_| format!("{text} is not an integer"))?;
+    Ok(if negative { -value } else { value })
+}
+
+// Parses a decimal integer of any length reduced by `modulo`, without a modulo it has to fit in an i128
+pub fn parse_reduced_integer(text: &str, modulo: i128) -> Result<i128, String> {
+    let value = parse_big_integer(text)?;
+    if modulo == 0 {
+        return i128::try_from(&value).map_err(|_| format!("{} is too large to use without a modulo, except as a term of a custom sequence or a file in a big integer wall", text.trim()));
+    }
+    let m = BigInt::from(modulo);
+    Ok(i128::try_from(((value % &m) + &m) % &m).unwrap())
+}
+
+fn is_comment(line: &str) -> bool {
+    line.is_empty() || line.starts_with('#')
+}
+
+fn parse_b_file<T>(contents: &str, parse: &dyn Fn(&str) -> Result<T, String>) -> Result<LoadedSequence<T>, String> {
+    let mut values = vec![];
+    let mut offset = 0;
+    let mut next_index: Option<i128> = None;
+
+    for (line_number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if is_comment(line) {
+            continue;
+        }
+        let mut parts = line.split_whitespace();
+        let (index, value) = match (parts.next(), parts.next()) {
+            (Some(i), Some(v)) => (i, v),
+            _ => { return Err(format!("line {} of the b-file is not of the form `n a(n)`", line_number+1)); }
+        };
+        let index: i128 = index.parse().map_err(|_| format!("could not parse the index on line {} of the b-file", line_number+1))?;
+
+        match next_index {
+            None => {
+                offset = index.try_into().map_err(|_| format!("the b-file offset {index} is too large"))?;
+            },
+            Some(expected) if expected != index => {
+                return Err(format!("line {} of the b-file has index {index}, expected {expected}", line_number+1));
+            },
+            Some(_) => {}
+        }
+        next_index = Some(index+1);
+
+        values.push(parse(value).map_err(|e| format!("line {} of the b-file: {e}", line_number+1))?);
+    }
+
+    Ok(LoadedSequence { values, offset })
+}
+
+fn parse_list<T>(contents: &str, parse: &dyn Fn(&str) -> Result<T, String>) -> Result<LoadedSequence<T>, String> {
+    let mut values = vec![];
+    for line in contents.lines() {
+        let line = line.trim();
+        if is_comment(line) {
+            continue;
+        }
+        for item in line.split(|c: char| c == ',' || c.is_whitespace()).filter(|s| !s.is_empty()) {
+            values.push(parse(item)?);
+        }
+    }
+    Ok(LoadedSequence { values, offset: 0 })
+}
+
+fn looks_like_b_file(contents: &str) -> bool {
+    let mut lines = contents.lines().map(|l| l.trim()).filter(|l| !is_comment(l)).peekable();
+    lines.peek().is_some() && lines.all(|l| l.split_whitespace().count() == 2 && !l.contains(','))
+}
+
+pub fn load_sequence_file(path: &str, format: SequenceFileFormat, modulo: i128) -> Result<LoadedSequence, String> {
+    load_file_with(path, format, &|text| parse_reduced_integer(text, modulo))
+}
+
+// Loads a sequence file whose terms are kept as big integers, for big integer walls
+pub fn load_big_sequence_file(path: &str, format: SequenceFileFormat) -> Result<LoadedSequence<BigInt>, String> {
+    load_file_with(path, format, &parse_big_integer)
+}
+
+fn load_file_with<T>(path: &str, format: SequenceFileFormat, parse: &dyn Fn(&str) -> Result<T, String>) -> Result<LoadedSequence<T>, String> {
+    let contents = fs::read_to_string(path).map_err(|e| format!("Could not read sequence file {path}: {e}"))?;
+
+    let loaded = match format {
+        SequenceFileFormat::BFile => parse_b_file(&contents, parse)?,
+        SequenceFileFormat::List => parse_list(&contents, parse)?,
+        SequenceFileFormat::Auto => {
+            if looks_like_b_file(&contents) {
+                parse_b_file(&contents, parse)?
+            } else {
+                parse_list(&contents, parse)?
+            }
+        }
+    };
+    if loaded.values.is_empty() {
+        return Err(format!("Sequence file {path} does not contain any values"));
+    }
+    Ok(loaded)
+}
+
+// Reads any file as a sequence of its bits or bytes, like the output of a random number generator
+pub fn load_binary_file(path: &str, unit: BinaryUnit) -> Result<LoadedSequence, String> {
+    let bytes = fs::read(path).map_err(|e| format!("Could not read binary file {path}: {e}"))?;
+    if bytes.is_empty() {
+        return Err(format!("Binary file {path} is empty"));
+    }
+    let values = match unit {
+        BinaryUnit::Bit => bytes.iter().flat_map(|b| (0..8).rev().map(move |i| i128::from((b >> i) & 1))).collect(),
+        BinaryUnit::Byte => bytes.iter().map(|&b| i128::from(b)).collect()
+    };
+    Ok(LoadedSequence { values, offset: 0 })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reduces_by_the_modulo() {
+        assert_eq!(parse_reduced_integer("12", 5), Ok(2));
+        assert_eq!(parse_reduced_integer("-5", 7), Ok(2));
+        assert_eq!(parse_reduced_integer("+5", 7), Ok(5));
+        assert_eq!(parse_reduced_integer("123456789012345678901234567890123456789", 1_000_000_007), Ok(741_412_909));
+        assert_eq!(parse_reduced_integer("-170141183460469231731687303715884105729", 2), Ok(1));
+    }
+
+    #[test]
+    fn keeps_the_value_without_a_modulo() {
+        assert_eq!(parse_reduced_integer("-42", 0), Ok(-42));
+        assert_eq!(parse_reduced_integer("170141183460469231731687303715884105727", 0), Ok(i128::MAX));
+        assert!(parse_reduced_integer("170141183460469231731687303715884105728", 0).is_err());
+    }
+
+    #[test]
+    fn rejects_what_is_not_an_integer() {
+        for text in ["", "-", "12a", "1.5", "1e5", "- 3"] {
+            assert!(parse_reduced_integer(text, 7).is_err(), "{text:?} is not an integer");
+        }
+    }
+
+    fn parse(text: &str) -> Result<i128, String> {
+        parse_reduced_integer(text, 0)
+    }
+
+    #[test]
+    fn parses_b_files() {
+        let loaded = parse_b_file("# A000045\n\n5 5\n6 8\n7 13\n", &parse).unwrap();
+        assert_eq!((loaded.values, loaded.offset), (vec![5, 8, 13], 5));
+        assert!(parse_b_file("0 1\n2 1\n", &parse).is_err());
+        assert!(parse_b_file("0\n", &parse).is_err());
+    }
+
+    #[test]
+    fn parses_lists() {
+        let loaded = parse_list("1, 2,3\n# comment\n4 5\n", &parse).unwrap();
+        assert_eq!((loaded.values, loaded.offset), (vec![1, 2, 3, 4, 5], 0));
+        assert!(parse_list("1, x", &parse).is_err());
+    }
+
+    #[test]
+    fn recognises_b_files() {
+        assert!(looks_like_b_file("# A000045\n0 0\n1 1\n"));
+        assert!(!looks_like_b_file("0 1 1 2\n"));
+        assert!(!looks_like_b_file("0, 1\n"));
+        assert!(!looks_like_b_file("# only comments\n"));
+    }
+}
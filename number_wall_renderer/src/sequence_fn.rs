@@ -0,0 +1,129 @@
+use std::{cell::RefCell, rc::Rc};
+use num_bigint::BigInt;
+
+thread_local! {
+    // the first term that could not be found, as the functions of sequences cannot return errors
+    static TERM_ERROR: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+// Records that a term could not be found, only the first error is kept until it is checked
+pub fn term_error(message: String) {
+    TERM_ERROR.with(|e| {
+        e.borrow_mut().get_or_insert(message);
+    });
+}
+
+// The first term that could not be found since the last check, as an error
+pub fn check_terms() -> Result<(), String> {
+    match TERM_ERROR.with(|e| e.borrow_mut().take()) {
+        Some(message) => Err(message),
+        None => Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum TermOperation {
+    Add,
+    Sub,
+    Mul
+}
+
+// `a op b` reduced by `modulo`. Mod a modulo whose values are too large for the operation to fit in an i128 it is done with big integers,
+// over the integers an overflow is recorded as a term error, described by `term`, and the result is 0.
+pub fn term_operation<T>(a: i128, b: i128, operation: TermOperation, modulo: i128, term: T) -> i128
+    where
+        T: Fn() -> String
+{
+    let (a, b) = if modulo > 0 { (a.rem_euclid(modulo), b.rem_euclid(modulo)) } else { (a, b) };
+    let result = match operation {
+        TermOperation::Add => a.checked_add(b),
+        TermOperation::Sub => a.checked_sub(b),
+        TermOperation::Mul => a.checked_mul(b)
+    };
+    match result {
+        Some(v) if modulo > 0 => v.rem_euclid(modulo),
+        Some(v) => v,
+        None if modulo > 0 => {
+            let (a, b, m) = (BigInt::from(a), BigInt::from(b), BigInt::from(modulo));
+            let v = match operation {
+                TermOperation::Add => a + b,
+                TermOperation::Sub => a - b,
+                TermOperation::Mul => a * b
+            };
+            i128::try_from(((v % &m) + &m) % &m).unwrap()
+        },
+        None => {
+            term_error(format!("{} overflows an i128, use a modulo to reduce it", term()));
+            0
+        }
+    }
+}
+
+// A sequence that can be evaluated at any index in its domain.
+// `first` and `last` bound the domain, `None` means the sequence goes on forever in that direction.
+#[derive(Clone)]
+pub struct SequenceFn {
+    pub func: Rc<dyn Fn(isize) -> i128>,
+    pub first: Option<isize>,
+    pub last: Option<isize>
+}
+
+impl SequenceFn {
+    pub fn finite(values: Vec<i128>, start: isize) -> Self {
+        let last = start + values.len() as isize - 1;
+        SequenceFn {
+            func: Rc::new(move |n| values[(n-start) as usize]),
+            first: Some(start),
+            last: Some(last)
+        }
+    }
+
+    pub fn one_sided<F>(func: F) -> Self
+        where
+            F: Fn(usize) -> i128 + 'static
+    {
+        SequenceFn {
+            func: Rc::new(move |n| func(n as usize)),
+            first: Some(0),
+            last: None
+        }
+    }
+
+    pub fn two_sided<F>(func: F) -> Self
+        where
+            F: Fn(isize) -> i128 + 'static
+    {
+        SequenceFn {
+            func: Rc::new(func),
+            first: None,
+            last: None
+        }
+    }
+
+    pub fn value(&self, n: isize) -> i128 {
+        (self.func)(n)
+    }
+
+    pub fn contains(&self, n: isize) -> bool {
+        self.first.is_none_or(|f| n >= f) && self.last.is_none_or(|l| n <= l)
+    }
+
+    // the finite sequence of the values from `start` to `end`
+    pub fn sample(&self, start: isize, end: isize) -> Result<SequenceFn, String> {
+        if end < start {
+            return Err(format!("the sample end ({end}) is before its start ({start})"));
+        }
+        if !self.contains(start) || !self.contains(end) {
+            return Err(format!("the sample from {start} to {end} is outside of the sequence"));
+        }
+        Ok(SequenceFn::finite((start..=end).map(|n| self.value(n)).collect(), start))
+    }
+
+    // every value of a finite sequence, starting from `first`
+    pub fn values(&self) -> Option<Vec<i128>> {
+        match (self.first, self.last) {
+            (Some(first), Some(last)) => Some((first..=last).map(|n| self.value(n)).collect()),
+            _ => None
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use std::rc::Rc;
+
+pub struct SequenceInfo {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub oeis: Option<&'static str>,
+    // the modulo the sequence is usually drawn with
+    pub natural_modulo: Option<i128>
+}
+
+// A built in sequence that can be evaluated at any index.
+// `one_sided` is used by left const walls and only needs the terms from 0 onwards,
+// `two_sided` is used by bi directional walls and also defines the negative indices.
+pub trait SequenceSource {
+    fn info(&self) -> SequenceInfo;
+
+    fn two_sided(&self, n: isize) -> i128;
+
+    fn one_sided(&self, n: usize) -> i128 {
+        self.two_sided(n as isize)
+    }
+}
+
+pub struct Rook;
+pub struct Knight;
+pub struct Pagoda;
+pub struct Rueppel;
+pub struct Zigzag;
+
+fn rook(mut n: isize) -> i128 {
+    if n == 0 {
+        return 0;
+    }
+    if n < 0 {
+        return (1-rook(-n)) % 2;
+    }
+
+    while n % 2 == 0 {
+        n >>= 1
+    }
+    ((n>>1) % 2) as i128
+}
+
+impl SequenceSource for Rook {
+    fn info(&self) -> SequenceInfo {
+        SequenceInfo {
+            name: "rook",
+            description: "The bit to the left of the lowest set bit of n",
+            oeis: Some("A038189"),
+            natural_modulo: Some(2)
+        }
+    }
+
+    fn two_sided(&self, n: isize) -> i128 {
+        rook(n)
+    }
+}
+
+impl SequenceSource for Knight {
+    fn info(&self) -> SequenceInfo {
+        SequenceInfo {
+            name: "knight",
+            description: "rook(n+1) - rook(n-1), taken mod 2",
+            oeis: None,
+            natural_modulo: Some(2)
+        }
+    }
+
+    fn two_sided(&self, n: isize) -> i128 {
+        (rook(n+1) - rook(n-1)).rem_euclid(2)
+    }
+}
+
+impl SequenceSource for Pagoda {
+    fn info(&self) -> SequenceInfo {
+        SequenceInfo {
+            name: "pagoda",
+            description: "rook(n+1) - rook(n-1), taken mod 3",
+            oeis: None,
+            natural_modulo: Some(3)
+        }
+    }
+
+    fn two_sided(&self, n: isize) -> i128 {
+        (rook(n+1) - rook(n-1)).rem_euclid(3)
+    }
+}
+
+impl SequenceSource for Rueppel {
+    fn info(&self) -> SequenceInfo {
+        SequenceInfo {
+            name: "rueppel",
+            description: "1 if n+1 is a power of 2, otherwise 0, mirrored for negative n",
+            oeis: Some("A036987"),
+            natural_modulo: Some(2)
+        }
+    }
+
+    fn two_sided(&self, n: isize) -> i128 {
+        if ((n.unsigned_abs()) + 1).is_power_of_two() {
+            1
+        } else {
+            0
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ZigZagSequenceItem {
+    A, B, C, D, E, F
+}
+impl ZigZagSequenceItem {
+    fn expand(self) -> [Self; 3] {
+        match self {
+            Self::A => [Self::A, Self::C, Self::B],
+            Self::B => [Self::B, Self::C, Self::B],
+            Self::C => [Self::E, Self::D, Self::F],
+            Self::D => [Self::D, Self::D, Self::D],
+            Self::E => [Self::E, Self::D, Self::D],
+            Self::F => [Self::D, Self::D, Self::F]
+        }
+    }
+
+    fn value(self) -> i128 {
+        match self {
+            Self::A => 1,
+            Self::B => 0,
+            Self::C => 1,
+            Self::D => 0,
+            Self::E => 2,
+            Self::F => 2
+        }
+    }
+}
+fn get_zigzag_position(position: usize) -> ZigZagSequenceItem {
+    if position == 0 { return ZigZagSequenceItem::A; };
+    if position == 1 { return ZigZagSequenceItem::C; };
+    if position == 2 { return ZigZagSequenceItem::B; };
+
+    let expand_position = position % 3;
+
+    let base_position = position-expand_position;
+
+    let previous_position = base_position/3;
+    get_zigzag_position(previous_position).expand()[expand_position]
+}
+
+impl SequenceSource for Zigzag {
+    fn info(&self) -> SequenceInfo {
+        SequenceInfo {
+            name: "zigzag",
+            description: "A ternary substitution sequence, mirrored and negated mod 3 for negative n",
+            oeis: None,
+            natural_modulo: Some(3)
+        }
+    }
+
+    fn two_sided(&self, n: isize) -> i128 {
+        if n < 0 {
+            (3-get_zigzag_position(n.unsigned_abs() - 1).value()).rem_euclid(3)
+        } else {
+            get_zigzag_position(n as usize).value()
+        }
+    }
+}
+
+pub fn builtin_sequences() -> Vec<Rc<dyn SequenceSource>> {
+    vec![Rc::new(Knight), Rc::new(Rook), Rc::new(Pagoda), Rc::new(Rueppel), Rc::new(Zigzag)]
+}
+
+pub fn find_sequence(name: &str) -> Option<Rc<dyn SequenceSource>> {
+    let name = name.to_ascii_lowercase();
+    builtin_sequences().into_iter().find(|s| s.info().name == name)
+}
@@ -0,0 +1,331 @@
+use std::collections::BTreeMap;
+use std::fs;
+use json::JsonValue;
+use num_bigint::BigInt;
+use num_traits::{One, Zero, Signed};
+
+use crate::Boundary;
+use crate::big_wall::WallArithmetic;
+
+// How the image of a symbolic wall is coloured
+#[derive(Debug, Clone, Copy)]
+pub enum SymbolicColouring {
+    Degree,
+    // only whether each cell is identically zero
+    Zero
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum TableFormat {
+    Text,
+    Latex
+}
+
+pub struct SymbolicSettings {
+    pub colouring: SymbolicColouring,
+    // the colour of the highest degree when colouring by degree
+    pub degree_colour: [u8; 3],
+    pub table_file: Option<String>,
+    pub table_format: TableFormat
+}
+
+// A term of a symbolic sequence, an integer or a symbol like "a0"
+#[derive(Debug, Clone)]
+pub enum SymbolicTerm {
+    Integer(BigInt),
+    Symbol(String)
+}
+
+pub fn parse_term(value: &JsonValue) -> Result<SymbolicTerm, String> {
+    if let Some(v) = value.as_i64() {
+        return Ok(SymbolicTerm::Integer(v.into()));
+    }
+    let Some(text) = value.as_str() else {
+        return Err("it is not a number or a symbol".into());
+    };
+    let text = text.trim();
+    if let Ok(v) = text.parse::<BigInt>() {
+        return Ok(SymbolicTerm::Integer(v));
+    }
+    let mut chars = text.chars();
+    if chars.next().is_some_and(|c| c.is_ascii_alphabetic()) && chars.all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        Ok(SymbolicTerm::Symbol(text.to_owned()))
+    } else {
+        Err(format!("{text} is not an integer or a symbol, symbols are names like a0"))
+    }
+}
+
+// A polynomial in the symbols of a wall with integer coefficients, keyed by the exponent of each symbol.
+// The monomials are in lexicographic order, so the last term is the leading term when dividing.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Polynomial {
+    terms: BTreeMap<Vec<u32>, BigInt>
+}
+
+impl Polynomial {
+    fn term(monomial: Vec<u32>, coefficient: BigInt) -> Self {
+        let mut p = Polynomial::default();
+        p.add_term(monomial, coefficient);
+        p
+    }
+
+    fn add_term(&mut self, monomial: Vec<u32>, coefficient: BigInt) {
+        let sum = self.terms.remove(&monomial).unwrap_or_default() + coefficient;
+        if !sum.is_zero() {
+            self.terms.insert(monomial, sum);
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub fn degree(&self) -> u32 {
+        self.terms.keys().map(|m| m.iter().sum()).max().unwrap_or(0)
+    }
+}
+
+// Exact polynomial arithmetic in the symbols of a sequence
+pub struct SymbolicArithmetic {
+    names: Vec<String>
+}
+
+impl SymbolicArithmetic {
+    // The symbols of `terms` are numbered in the order they first appear
+    pub fn new(terms: &[SymbolicTerm]) -> Self {
+        let mut names: Vec<String> = vec![];
+        for term in terms {
+            if let SymbolicTerm::Symbol(name) = term {
+                if !names.contains(name) {
+                    names.push(name.clone());
+                }
+            }
+        }
+        SymbolicArithmetic { names }
+    }
+
+    pub fn polynomial(&self, term: &SymbolicTerm) -> Polynomial {
+        match term {
+            SymbolicTerm::Integer(v) => Polynomial::term(vec![0; self.names.len()], v.clone()),
+            SymbolicTerm::Symbol(name) => {
+                let monomial = self.names.iter().map(|n| u32::from(n == name)).collect();
+                Polynomial::term(monomial, BigInt::one())
+            }
+        }
+    }
+
+    fn format_monomial(&self, monomial: &[u32], format: TableFormat) -> String {
+        let factors: Vec<String> = self.names.iter().zip(monomial).filter(|(_, e)| **e > 0).map(|(name, e)| {
+            match format {
+                TableFormat::Text if *e == 1 => name.clone(),
+                TableFormat::Text => format!("{name}^{e}"),
+                TableFormat::Latex => {
+                    // a trailing number is written as a subscript, a0 as a_{0}
+                    let split = name.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+                    let name = if split > 0 && split < name.len() { format!("{}_{{{}}}", &name[..split], &name[split..]) } else { name.clone() };
+                    if *e == 1 { name } else { format!("{name}^{{{e}}}") }
+                }
+            }
+        }).collect();
+        factors.join(match format { TableFormat::Text => "*", TableFormat::Latex => " " })
+    }
+
+    // The terms are written from the highest degree down
+    pub fn format(&self, p: &Polynomial, format: TableFormat) -> String {
+        if p.is_zero() {
+            return "0".into();
+        }
+        let mut terms: Vec<(&Vec<u32>, &BigInt)> = p.terms.iter().collect();
+        terms.sort_by_key(|(m, _)| (std::cmp::Reverse(m.iter().sum::<u32>()), std::cmp::Reverse(*m)));
+
+        let mut text = String::new();
+        for (i, (monomial, coefficient)) in terms.into_iter().enumerate() {
+            match (i, coefficient.is_negative()) {
+                (0, true) => text.push('-'),
+                (0, false) => {},
+                (_, true) => text.push_str(" - "),
+                (_, false) => text.push_str(" + ")
+            }
+            let magnitude = coefficient.abs();
+            let factors = self.format_monomial(monomial, format);
+            if factors.is_empty() {
+                text.push_str(&magnitude.to_string());
+            } else if magnitude.is_one() {
+                text.push_str(&factors);
+            } else {
+                let separator = match format { TableFormat::Text => "*", TableFormat::Latex => " " };
+                text.push_str(&format!("{magnitude}{separator}{factors}"));
+            }
+        }
+        text
+    }
+}
+
+impl WallArithmetic for SymbolicArithmetic {
+    type Value = Polynomial;
+
+    fn zero(&self) -> Polynomial {
+        Polynomial::default()
+    }
+
+    fn one(&self) -> Polynomial {
+        Polynomial::term(vec![0; self.names.len()], BigInt::one())
+    }
+
+    fn is_zero(&self, value: &Polynomial) -> bool {
+        value.is_zero()
+    }
+
+    fn add(&self, a: &Polynomial, b: &Polynomial) -> Polynomial {
+        let mut sum = a.clone();
+        for (monomial, coefficient) in &b.terms {
+            sum.add_term(monomial.clone(), coefficient.clone());
+        }
+        sum
+    }
+
+    fn sub(&self, a: &Polynomial, b: &Polynomial) -> Polynomial {
+        let mut difference = a.clone();
+        for (monomial, coefficient) in &b.terms {
+            difference.add_term(monomial.clone(), -coefficient);
+        }
+        difference
+    }
+
+    fn mul(&self, a: &Polynomial, b: &Polynomial) -> Polynomial {
+        let mut product = Polynomial::default();
+        for (m, c) in &a.terms {
+            for (n, d) in &b.terms {
+                product.add_term(m.iter().zip(n).map(|(x, y)| x + y).collect(), c * d);
+            }
+        }
+        product
+    }
+
+    // Division by the leading term, which leaves no remainder when `den` divides `num`
+    fn div(&self, num: &Polynomial, den: &Polynomial) -> Result<Polynomial, String> {
+        let Some((lead, lead_coefficient)) = den.terms.last_key_value() else {
+            return Err(format!("{} was divided by zero", self.format(num, TableFormat::Text)));
+        };
+        let mut remainder = num.clone();
+        let mut quotient = Polynomial::default();
+        while let Some((monomial, coefficient)) = remainder.terms.last_key_value() {
+            let exponents: Option<Vec<u32>> = monomial.iter().zip(lead).map(|(a, b)| a.checked_sub(*b)).collect();
+            match exponents {
+                Some(exponents) if (coefficient % lead_coefficient).is_zero() => {
+                    let term = Polynomial::term(exponents, coefficient / lead_coefficient);
+                    remainder = self.sub(&remainder, &self.mul(&term, den));
+                    quotient = self.add(&quotient, &term);
+                },
+                _ => {
+                    return Err(format!("{} is not divisible by {}", self.format(num, TableFormat::Text), self.format(den, TableFormat::Text)));
+                }
+            }
+        }
+        Ok(quotient)
+    }
+}
+
+// Writes the cells of a symbolic wall as a table with a row for each row of the wall, cells that are not defined are written as ?
+pub fn write_table(lines: &[Vec<Option<Polynomial>>], boundary: &Boundary, arithmetic: &SymbolicArithmetic, format: TableFormat, path: &str) -> Result<(), String> {
+    let header: Vec<String> = (boundary.left..=boundary.right).map(|c| c.to_string()).collect();
+    let rows: Vec<(String, Vec<String>)> = lines.iter().enumerate().map(|(y, line)| {
+        let cells = line.iter().map(|cell| cell.as_ref().map_or("?".into(), |p| arithmetic.format(p, format))).collect();
+        ((boundary.top + y).to_string(), cells)
+    }).collect();
+
+    let table = match format {
+        TableFormat::Text => {
+            let label_width = rows.iter().map(|(label, _)| label.len()).max().unwrap_or(0);
+            let widths: Vec<usize> = (0..header.len())
+                .map(|x| rows.iter().map(|(_, cells)| cells[x].len()).chain([header[x].len()]).max().unwrap_or(0))
+                .collect();
+            let mut table = String::new();
+            for (label, cells) in [(String::new(), header)].into_iter().chain(rows) {
+                let mut line = format!("{label:>label_width$}");
+                for (cell, width) in cells.iter().zip(&widths) {
+                    line.push_str(&format!(" | {cell:^width$}"));
+                }
+                table.push_str(line.trim_end());
+                table.push('\n');
+            }
+            table
+        },
+        TableFormat::Latex => {
+            let mut table = format!("\\begin{{array}}{{r|{}}}\n", "c".repeat(header.len()));
+            table.push_str(&format!(" & {} \\\\\n\\hline\n", header.join(" & ")));
+            for (label, cells) in rows {
+                table.push_str(&format!("{label} & {} \\\\\n", cells.join(" & ")));
+            }
+            table.push_str("\\end{array}\n");
+            table
+        }
+    };
+    fs::write(path, table).map_err(|e| format!("Could not write {path}: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::big_wall::big_wall_lines;
+    use crate::determinant;
+
+    fn terms(values: &[&str]) -> Vec<SymbolicTerm> {
+        values.iter().map(|v| parse_term(&JsonValue::from(*v)).unwrap()).collect()
+    }
+
+    #[test]
+    fn parses_terms() {
+        assert!(matches!(parse_term(&JsonValue::from(-3)), Ok(SymbolicTerm::Integer(v)) if v == BigInt::from(-3)));
+        assert!(matches!(parse_term(&JsonValue::from(" a_0 ")), Ok(SymbolicTerm::Symbol(s)) if s == "a_0"));
+        assert!(matches!(parse_term(&JsonValue::from("123456789012345678901234567890")), Ok(SymbolicTerm::Integer(_))));
+        assert!(parse_term(&JsonValue::from("0a")).is_err());
+        assert!(parse_term(&JsonValue::from("a+b")).is_err());
+        assert!(parse_term(&JsonValue::from(true)).is_err());
+    }
+
+    #[test]
+    fn formats_polynomials() {
+        let values = terms(&["a0", "b", "2"]);
+        let arithmetic = SymbolicArithmetic::new(&values);
+        let [a, b, two] = [0, 1, 2].map(|i| arithmetic.polynomial(&values[i]));
+        // 2*a0^2 - a0*b + 2 - b
+        let p = arithmetic.sub(&arithmetic.add(&arithmetic.mul(&two, &arithmetic.mul(&a, &a)), &two), &arithmetic.add(&arithmetic.mul(&a, &b), &b));
+        assert_eq!(p.degree(), 2);
+        assert_eq!(arithmetic.format(&p, TableFormat::Text), "2*a0^2 - a0*b - b + 2");
+        assert_eq!(arithmetic.format(&p, TableFormat::Latex), "2 a_{0}^{2} - a_{0} b - b + 2");
+        assert_eq!(arithmetic.format(&arithmetic.sub(&p, &p), TableFormat::Text), "0");
+    }
+
+    #[test]
+    fn division() {
+        let values = terms(&["a", "b"]);
+        let arithmetic = SymbolicArithmetic::new(&values);
+        let [a, b] = [0, 1].map(|i| arithmetic.polynomial(&values[i]));
+        let product = arithmetic.mul(&arithmetic.add(&a, &b), &arithmetic.sub(&a, &b));
+        assert_eq!(arithmetic.div(&product, &arithmetic.sub(&a, &b)), Ok(arithmetic.add(&a, &b)));
+        assert!(arithmetic.div(&arithmetic.add(&product, &arithmetic.one()), &a).is_err());
+        assert!(arithmetic.div(&a, &arithmetic.zero()).is_err());
+    }
+
+    #[test]
+    fn symbolic_walls_match_their_determinants() {
+        let values = terms(&["a", "0", "b", "1", "c", "a", "0", "0", "b", "2"]);
+        let arithmetic = SymbolicArithmetic::new(&values);
+        let term = |n: isize| usize::try_from(n).ok().and_then(|i| values.get(i)).map(|t| arithmetic.polynomial(t));
+        let boundary = Boundary { top: 0, bottom: 5, left: 0, right: 9 };
+        let lines = big_wall_lines(term, &arithmetic, &boundary).unwrap();
+        for (y, line) in lines.iter().enumerate() {
+            for (x, cell) in line.iter().enumerate() {
+                let expected = if y == 0 { Some(arithmetic.zero()) } else { determinant::toeplitz(&arithmetic, term, x as isize, y - 1).unwrap() };
+                // cells whose frame is not known are left undefined
+                if cell.is_some() {
+                    assert_eq!(cell, &expected, "row {y}, column {x}");
+                }
+            }
+        }
+        // the second row is s(n)^2 - s(n-1) s(n+1)
+        assert_eq!(lines[3][2].as_ref().map(|p| arithmetic.format(p, TableFormat::Text)), Some("b^2".into()));
+        assert_eq!(lines[3][4].as_ref().map(|p| arithmetic.format(p, TableFormat::Text)), Some("c^2 - a".into()));
+    }
+}
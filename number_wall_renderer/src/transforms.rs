@@ -0,0 +1,255 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::sequence_fn::{term_error, term_operation, SequenceFn, TermOperation};
+
+pub enum Transform {
+    // b(n) = a(n+k)
+    Shift(isize),
+    // b(n) = a(-n)
+    Reverse,
+    Negate,
+    Scale(i128),
+    AddConstant(i128),
+    // b(n) = a(kn)
+    Decimate(isize),
+    // b(2n) = a(n), b(2n+1) = other(n)
+    Interleave(SequenceFn),
+    // b(n) = a(n+1) - a(n)
+    Difference,
+    // b(n) = a(first) + ... + a(n), sums of two sided sequences start from index 0
+    PartialSums,
+    // b(n) = sum over k of C(n, k) a(k), counted from the first term
+    Binomial,
+    Modulo(i128)
+}
+
+fn reduce(value: i128, modulo: i128) -> i128 {
+    if modulo > 0 { value.rem_euclid(modulo) } else { value }
+}
+
+fn map_values<F>(sequence: SequenceFn, map: F) -> SequenceFn
+    where
+        F: Fn(i128) -> i128 + 'static
+{
+    let func = sequence.func;
+    SequenceFn { func: Rc::new(move |n| map(func(n))), ..sequence }
+}
+
+fn partial_sums(sequence: SequenceFn, modulo: i128) -> SequenceFn {
+    let func = sequence.func.clone();
+    let start = sequence.first.unwrap_or(0);
+    // b(start), b(start+1), ... and b(start-1), b(start-2), ... as far as they have been needed, each found from the one before
+    let (after, before): (RefCell<Vec<i128>>, RefCell<Vec<i128>>) = Default::default();
+    SequenceFn {
+        func: Rc::new(move |n| {
+            if n >= start {
+                let mut sums = after.borrow_mut();
+                while sums.len() as isize <= n - start {
+                    let k = start + sums.len() as isize;
+                    let previous = sums.last().copied().unwrap_or(0);
+                    sums.push(term_operation(previous, func(k), TermOperation::Add, modulo, || format!("the partial sum at {k}")));
+                }
+                sums[(n - start) as usize]
+            } else {
+                // only reachable for two sided sequences, keeps b(n) - b(n-1) = a(n)
+                let mut sums = before.borrow_mut();
+                while (sums.len() as isize) < start - n {
+                    let k = start - 1 - sums.len() as isize;
+                    let sum = match sums.last() {
+                        Some(&previous) => term_operation(previous, func(k + 1), TermOperation::Sub, modulo, || format!("the partial sum at {k}")),
+                        None => 0
+                    };
+                    sums.push(sum);
+                }
+                sums[(start - 1 - n) as usize]
+            }
+        }),
+        ..sequence
+    }
+}
+
+fn binomial(sequence: SequenceFn, modulo: i128) -> Result<SequenceFn, String> {
+    let start = sequence.first.ok_or("the binomial transform needs a sequence with a first term")?;
+    let func = sequence.func.clone();
+    let available = sequence.last.map_or(usize::MAX, |l| (l-start+1).max(0) as usize);
+    // b(0..len) is calculated all at once with a difference table, and recalculated further when needed
+    let cache: RefCell<Vec<i128>> = RefCell::new(vec![]);
+    Ok(SequenceFn {
+        func: Rc::new(move |n| {
+            let Some(index) = usize::try_from(n-start).ok().filter(|&i| i < available) else {
+                term_error(format!("the binomial transform has no term at {n}"));
+                return 0;
+            };
+            let mut cache = cache.borrow_mut();
+            if index >= cache.len() {
+                let len = (index+1).max(2*cache.len()).min(available);
+                let mut table: Vec<i128> = (0..len).map(|k| reduce(func(start + k as isize), modulo)).collect();
+                let mut transformed = Vec::with_capacity(len);
+                for row in 0..len {
+                    transformed.push(table[0]);
+                    for k in 0..len-row-1 {
+                        table[k] = term_operation(table[k], table[k+1], TermOperation::Add, modulo, || format!("the binomial transform at {}", start + (row + 1) as isize));
+                    }
+                }
+                *cache = transformed;
+            }
+            cache[index]
+        }),
+        ..sequence
+    })
+}
+
+fn interleave(sequence: SequenceFn, other: SequenceFn) -> SequenceFn {
+    let first = match (sequence.first, other.first) {
+        (Some(a), Some(b)) => Some(2*a.max(b)),
+        (a, b) => a.or(b).map(|f| 2*f)
+    };
+    let last = match (sequence.last, other.last) {
+        (Some(a), Some(b)) => Some(2*a.min(b) + 1),
+        (a, b) => a.or(b).map(|l| 2*l + 1)
+    };
+    let (even, odd) = (sequence.func, other.func);
+    SequenceFn {
+        func: Rc::new(move |n| if n.rem_euclid(2) == 0 { even(n.div_euclid(2)) } else { odd(n.div_euclid(2)) }),
+        first,
+        last
+    }
+}
+
+pub fn apply_transform(sequence: SequenceFn, transform: Transform, modulo: i128) -> Result<SequenceFn, String> {
+    Ok(match transform {
+        Transform::Shift(k) => {
+            let func = sequence.func;
+            SequenceFn {
+                func: Rc::new(move |n| func(n+k)),
+                first: sequence.first.map(|f| f-k),
+                last: sequence.last.map(|l| l-k)
+            }
+        },
+        Transform::Reverse => {
+            let func = sequence.func;
+            SequenceFn {
+                func: Rc::new(move |n| func(-n)),
+                first: sequence.last.map(|l| -l),
+                last: sequence.first.map(|f| -f)
+            }
+        },
+        Transform::Negate => map_values(sequence, move |v| term_operation(0, v, TermOperation::Sub, modulo, || format!("negating {v}"))),
+        Transform::Scale(c) => map_values(sequence, move |v| term_operation(c, v, TermOperation::Mul, modulo, || format!("scaling {v} by {c}"))),
+        Transform::AddConstant(c) => map_values(sequence, move |v| term_operation(v, c, TermOperation::Add, modulo, || format!("adding {c} to {v}"))),
+        Transform::Modulo(m) => map_values(sequence, move |v| v.rem_euclid(m)),
+        Transform::Decimate(k) => {
+            let func = sequence.func;
+            SequenceFn {
+                func: Rc::new(move |n| func(k*n)),
+                first: sequence.first.map(|f| f.div_euclid(k) + if f.rem_euclid(k) == 0 { 0 } else { 1 }),
+                last: sequence.last.map(|l| l.div_euclid(k))
+            }
+        },
+        Transform::Interleave(other) => interleave(sequence, other),
+        Transform::Difference => {
+            let func = sequence.func;
+            SequenceFn {
+                func: Rc::new(move |n| term_operation(func(n+1), func(n), TermOperation::Sub, modulo, || format!("the difference at {n}"))),
+                first: sequence.first,
+                last: sequence.last.map(|l| l-1)
+            }
+        },
+        Transform::PartialSums => partial_sums(sequence, modulo),
+        Transform::Binomial => binomial(sequence, modulo)?
+    })
+}
+
+pub fn apply_transforms(mut sequence: SequenceFn, transforms: Vec<Transform>, modulo: i128) -> Result<SequenceFn, String> {
+    for transform in transforms {
+        sequence = apply_transform(sequence, transform, modulo)?;
+    }
+    if let (Some(first), Some(last)) = (sequence.first, sequence.last) {
+        if last < first {
+            return Err("the transformed sequence has no terms left".into());
+        }
+    }
+    Ok(sequence)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sequence_fn::check_terms;
+
+    fn naturals() -> SequenceFn {
+        SequenceFn::one_sided(|n| n as i128)
+    }
+
+    fn transformed(sequence: SequenceFn, transforms: Vec<Transform>, modulo: i128) -> SequenceFn {
+        apply_transforms(sequence, transforms, modulo).unwrap()
+    }
+
+    fn terms(sequence: &SequenceFn, range: std::ops::Range<isize>) -> Vec<i128> {
+        range.map(|n| sequence.value(n)).collect()
+    }
+
+    #[test]
+    fn reindexing() {
+        let shifted = transformed(naturals(), vec![Transform::Shift(3)], 0);
+        assert_eq!((shifted.first, shifted.value(-3), shifted.value(0)), (Some(-3), 0, 3));
+        let reversed = transformed(SequenceFn::finite(vec![1, 2, 3], 2), vec![Transform::Reverse], 0);
+        assert_eq!((reversed.first, reversed.last, reversed.values()), (Some(-4), Some(-2), Some(vec![3, 2, 1])));
+        let decimated = transformed(SequenceFn::finite((0..10).collect(), -3), vec![Transform::Decimate(3)], 0);
+        assert_eq!((decimated.first, decimated.values()), (Some(-1), Some(vec![0, 3, 6, 9])));
+        let interleaved = transformed(naturals(), vec![Transform::Interleave(SequenceFn::one_sided(|n| -(n as i128)))], 0);
+        assert_eq!(terms(&interleaved, 0..6), vec![0, 0, 1, -1, 2, -2]);
+    }
+
+    #[test]
+    fn pointwise_transforms_reduce_by_the_modulo() {
+        let sequence = transformed(naturals(), vec![Transform::Negate, Transform::Scale(3), Transform::AddConstant(2)], 5);
+        assert_eq!(terms(&sequence, 0..5), vec![2, 4, 1, 3, 0]);
+        let sequence = transformed(naturals(), vec![Transform::Modulo(3)], 0);
+        assert_eq!(terms(&sequence, 0..5), vec![0, 1, 2, 0, 1]);
+    }
+
+    #[test]
+    fn differences_undo_partial_sums() {
+        let squares = SequenceFn::two_sided(|n| (n*n) as i128);
+        let sums = transformed(squares, vec![Transform::PartialSums], 0);
+        assert_eq!(terms(&sums, 0..5), vec![0, 1, 5, 14, 30]);
+        let differences = transformed(sums, vec![Transform::Difference], 0);
+        assert_eq!(terms(&differences, -5..5), (-5..5).map(|n: i128| (n+1)*(n+1)).collect::<Vec<i128>>());
+        check_terms().unwrap();
+    }
+
+    #[test]
+    fn binomial_transform() {
+        let ones = transformed(SequenceFn::one_sided(|_| 1), vec![Transform::Binomial], 0);
+        assert_eq!(terms(&ones, 0..8), (0..8).map(|n| 1 << n).collect::<Vec<i128>>());
+        let ones = transformed(SequenceFn::one_sided(|_| 1), vec![Transform::Binomial], 1_000_003);
+        assert_eq!(ones.value(200), (0..200).fold(1, |p, _| 2*p % 1_000_003));
+        check_terms().unwrap();
+
+        let finite = transformed(SequenceFn::finite(vec![1, 2, 3], 0), vec![Transform::Binomial], 0);
+        assert_eq!(finite.values(), Some(vec![1, 3, 8]));
+        finite.value(3);
+        assert!(check_terms().is_err());
+        assert!(apply_transforms(SequenceFn::two_sided(|_| 1), vec![Transform::Binomial], 0).is_err());
+    }
+
+    #[test]
+    fn overflow_is_reported_without_a_modulo() {
+        let doubled = transformed(SequenceFn::one_sided(|_| i128::MAX / 2 + 1), vec![Transform::Scale(2)], 0);
+        doubled.value(0);
+        assert!(check_terms().is_err());
+        let sums = transformed(SequenceFn::one_sided(|_| i128::MAX / 2 + 1), vec![Transform::PartialSums], 0);
+        sums.value(1);
+        assert!(check_terms().is_err());
+        let sums = transformed(SequenceFn::one_sided(|_| i128::MAX / 2 + 1), vec![Transform::PartialSums], 7);
+        // 2^126 = 1 mod 7
+        assert_eq!(sums.value(1), 2);
+        check_terms().unwrap();
+    }
+
+    #[test]
+    fn nothing_left() {
+        assert!(apply_transforms(SequenceFn::finite(vec![1], 0), vec![Transform::Difference], 0).is_err());
+    }
+}
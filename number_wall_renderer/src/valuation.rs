@@ -0,0 +1,84 @@
+use num_bigint::BigInt;
+use num_traits::Zero;
+
+// Colours the cells of an integer wall by their p-adic valuation, so the structure mod p, p^2, p^3, ... shows at once.
+// Zero has its own colour, the valuations from 0 to `max` follow a gradient from `low` to `high`
+// and larger valuations are coloured as `max`.
+#[derive(Debug, Clone, Copy)]
+pub struct ValuationColours {
+    pub prime: i128,
+    pub zero: [u8; 3],
+    pub low: [u8; 3],
+    pub high: [u8; 3],
+    pub max: u32
+}
+
+impl ValuationColours {
+    // `valuation` is `None` for zero
+    pub fn colour(&self, valuation: Option<u32>) -> [u8; 3] {
+        let Some(v) = valuation else {
+            return self.zero;
+        };
+        gradient(self.low, self.high, v, self.max)
+    }
+}
+
+// The colour at `value` on the way from `low` at 0 to `high` at `max`, values above `max` are `high`
+pub fn gradient(low: [u8; 3], high: [u8; 3], value: u32, max: u32) -> [u8; 3] {
+    let t = if max == 0 { 0.0 } else { value.min(max) as f64 / max as f64 };
+    std::array::from_fn(|i| (low[i] as f64 + (high[i] as f64 - low[i] as f64) * t).round() as u8)
+}
+
+pub fn valuation(mut value: i128, prime: i128) -> Option<u32> {
+    if value == 0 {
+        return None;
+    }
+    let mut v = 0;
+    while value % prime == 0 {
+        value /= prime;
+        v += 1;
+    }
+    Some(v)
+}
+
+pub fn big_valuation(value: &BigInt, prime: i128) -> Option<u32> {
+    if value.is_zero() {
+        return None;
+    }
+    let prime = BigInt::from(prime);
+    let mut value = value.clone();
+    let mut v = 0;
+    while (&value % &prime).is_zero() {
+        value /= &prime;
+        v += 1;
+    }
+    Some(v)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valuations() {
+        assert_eq!(valuation(0, 2), None);
+        assert_eq!(valuation(-48, 2), Some(4));
+        assert_eq!(valuation(-48, 3), Some(1));
+        assert_eq!(valuation(7, 5), Some(0));
+        let big = BigInt::from(3).pow(100) * 10;
+        assert_eq!(big_valuation(&big, 3), Some(100));
+        assert_eq!(big_valuation(&-big, 2), Some(1));
+        assert_eq!(big_valuation(&BigInt::zero(), 7), None);
+    }
+
+    #[test]
+    fn colours() {
+        let colours = ValuationColours { prime: 2, zero: [0, 0, 0], low: [0, 100, 200], high: [200, 100, 0], max: 4 };
+        assert_eq!(colours.colour(None), [0, 0, 0]);
+        assert_eq!(colours.colour(Some(0)), [0, 100, 200]);
+        assert_eq!(colours.colour(Some(1)), [50, 100, 150]);
+        assert_eq!(colours.colour(Some(4)), [200, 100, 0]);
+        assert_eq!(colours.colour(Some(9)), [200, 100, 0]);
+        assert_eq!(gradient([0, 0, 0], [255, 255, 255], 3, 0), [0, 0, 0]);
+    }
+}
@@ -0,0 +1,97 @@
+use num_bigint::BigInt;
+
+use crate::Boundary;
+use crate::big_wall::BigArithmetic;
+use crate::composite;
+use crate::determinant;
+use crate::random_sequence::splitmix64;
+
+// How many mismatches are printed, the rest are only counted
+const PRINTED_MISMATCHES: usize = 20;
+
+pub struct VerifySettings {
+    // how many cells are checked, every cell of the region if `None`
+    pub samples: Option<usize>,
+    pub seed: u64
+}
+
+pub struct Mismatch {
+    pub row: usize,
+    pub column: isize,
+    pub wall: i128,
+    pub determinant: BigInt
+}
+
+pub struct VerifyReport {
+    pub checked: usize,
+    // cells whose determinant needs terms that are not known
+    pub skipped: usize,
+    pub mismatches: Vec<Mismatch>
+}
+
+impl VerifyReport {
+    pub fn summary(&self) -> String {
+        format!("{} cells checked, {} skipped, {} mismatches", self.checked, self.skipped, self.mismatches.len())
+    }
+
+    pub fn print_mismatches(&self) {
+        for m in self.mismatches.iter().take(PRINTED_MISMATCHES) {
+            eprintln!("row {}, column {}: the wall has {}, the determinant is {}", m.row, m.column, m.wall, m.determinant);
+        }
+        if self.mismatches.len() > PRINTED_MISMATCHES {
+            eprintln!("and {} more mismatches", self.mismatches.len() - PRINTED_MISMATCHES);
+        }
+    }
+}
+
+// The cells to check as (row, column) indices into the lines, a random choice of `samples` different cells if it is given
+fn chosen_cells(height: usize, width: usize, settings: &VerifySettings) -> Vec<(usize, usize)> {
+    let mut cells: Vec<usize> = (0..height*width).collect();
+    if let Some(samples) = settings.samples.filter(|&s| s < cells.len()) {
+        // the first `samples` steps of a Fisher-Yates shuffle
+        for i in 0..samples {
+            let j = i + (splitmix64(settings.seed ^ splitmix64(i as u64)) % (cells.len() - i) as u64) as usize;
+            cells.swap(i, j);
+        }
+        cells.truncate(samples);
+    }
+    cells.into_iter().map(|i| (i / width, i % width)).collect()
+}
+
+// Recomputes the cells of a rendered wall as Toeplitz determinants, independently of how the wall was generated.
+// The cell in row r and column c is det(s(c+i-j)) of size r-1, row 0 is all zeros and row 1 all ones.
+// Mod a prime the determinants are found mod the prime, otherwise over the integers and then reduced by the modulo.
+pub fn verify_wall<F>(lines: &[Vec<i128>], terms: F, modulo: i128, boundary: &Boundary, settings: &VerifySettings) -> Result<VerifyReport, String>
+    where
+        F: Fn(isize) -> Option<i128>
+{
+    let arithmetic = if modulo > 1 && composite::is_prime(modulo) { BigArithmetic::new(modulo.into()) } else { BigArithmetic::new(BigInt::from(0)) };
+    let terms = |n: isize| terms(n).map(BigInt::from);
+    let width = lines.first().map_or(0, |l| l.len());
+
+    let mut report = VerifyReport { checked: 0, skipped: 0, mismatches: vec![] };
+    for (y, x) in chosen_cells(lines.len(), width, settings) {
+        let (row, column) = (boundary.top + y, boundary.left + x as isize);
+        let expected = if row == 0 {
+            Some(BigInt::from(0))
+        } else {
+            determinant::toeplitz(&arithmetic, &terms, column, row - 1)?
+        };
+        let Some(mut expected) = expected else {
+            report.skipped += 1;
+            continue;
+        };
+        let mut wall = lines[y][x];
+        if modulo > 0 {
+            let m = BigInt::from(modulo);
+            expected = ((expected % &m) + &m) % &m;
+            wall = wall.rem_euclid(modulo);
+        }
+        report.checked += 1;
+        if BigInt::from(wall) != expected {
+            report.mismatches.push(Mismatch { row, column, wall, determinant: expected });
+        }
+    }
+    report.mismatches.sort_by_key(|m| (m.row, m.column));
+    Ok(report)
+}
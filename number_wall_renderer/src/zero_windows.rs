@@ -0,0 +1,262 @@
+use std::{collections::BTreeMap, fs};
+
+use crate::Boundary;
+use crate::valuation::gradient;
+
+// A maximal square block of zeros in a rendered wall.
+// Windows that touch the edge of the boundary are `cut_off`, only `width` by `height` of them is seen,
+// and their real size may be larger than `size`, which is the larger of the two.
+#[derive(Debug, Clone, Copy)]
+pub struct ZeroWindow {
+    pub top: usize,
+    pub left: isize,
+    pub size: usize,
+    pub width: usize,
+    pub height: usize,
+    pub cut_off: bool
+}
+
+pub fn find_zero_windows(lines: &[Vec<i128>], boundary: &Boundary) -> Vec<ZeroWindow> {
+    find_zero_windows_masked(lines, boundary, |_, _| true)
+}
+
+// The windows of a wall whose cells are not i128s, cells where `is_zero` is `None` are not defined
+pub fn find_zero_windows_by<T, Z, D>(lines: &[Vec<T>], boundary: &Boundary, is_zero: Z, defined: D) -> Vec<ZeroWindow>
+    where
+        Z: Fn(&T) -> Option<bool>,
+        D: Fn(usize, isize) -> bool
+{
+    label_zero_windows_by(lines, boundary, is_zero, defined).0
+}
+
+// As `find_zero_windows_by`, along with the index of the window that each cell of `lines` is in, `None` for cells outside of every window
+pub fn label_zero_windows_by<T, Z, D>(lines: &[Vec<T>], boundary: &Boundary, is_zero: Z, defined: D) -> (Vec<ZeroWindow>, Vec<Vec<Option<usize>>>)
+    where
+        Z: Fn(&T) -> Option<bool>,
+        D: Fn(usize, isize) -> bool
+{
+    let zeros: Vec<Vec<Option<bool>>> = lines.iter().map(|line| line.iter().map(&is_zero).collect()).collect();
+    let values: Vec<Vec<i128>> = zeros.iter().map(|line| line.iter().map(|z| i128::from(*z != Some(true))).collect()).collect();
+    label_zero_windows(&values, boundary, |r, c| {
+        zeros[r - boundary.top][(c - boundary.left) as usize].is_some() && defined(r, c)
+    })
+}
+
+pub fn find_zero_windows_masked<D>(lines: &[Vec<i128>], boundary: &Boundary, defined: D) -> Vec<ZeroWindow>
+    where
+        D: Fn(usize, isize) -> bool
+{
+    label_zero_windows(lines, boundary, defined).0
+}
+
+// Zero windows are separated by their non zero inner frames, so every connected group of zeros is one window.
+// Cells where `defined(row, column)` is false are ignored, and windows next to them are cut off.
+// Row 0 is zero by definition rather than a window, so it is left out like a row of non zero cells.
+// Each zero cell is labelled with the index of its window, which is only a square when it is not cut off.
+pub fn label_zero_windows<D>(lines: &[Vec<i128>], boundary: &Boundary, defined: D) -> (Vec<ZeroWindow>, Vec<Vec<Option<usize>>>)
+    where
+        D: Fn(usize, isize) -> bool
+{
+    let is_defined = |x: usize, y: usize| defined(boundary.top + y, boundary.left + x as isize);
+    let is_zero = |x: usize, y: usize| boundary.top + y > 0 && lines[y][x] == 0;
+    let height = lines.len();
+    let width = lines.first().map_or(0, |l| l.len());
+
+    let mut labels: Vec<Vec<Option<usize>>> = vec![vec![None; width]; height];
+    let mut windows = vec![];
+    let mut stack = vec![];
+
+    for y in 0..height {
+        for x in 0..width {
+            if labels[y][x].is_some() || !is_zero(x, y) || !is_defined(x, y) {
+                continue;
+            }
+            let label = Some(windows.len());
+            let (mut min_x, mut max_x, mut min_y, mut max_y) = (x, x, y, y);
+            let mut touches_undefined = false;
+            labels[y][x] = label;
+            stack.push((x, y));
+            while let Some((cx, cy)) = stack.pop() {
+                min_x = min_x.min(cx);
+                max_x = max_x.max(cx);
+                min_y = min_y.min(cy);
+                max_y = max_y.max(cy);
+
+                let mut neighbours = vec![];
+                if cx > 0 { neighbours.push((cx-1, cy)); }
+                if cx+1 < width { neighbours.push((cx+1, cy)); }
+                if cy > 0 { neighbours.push((cx, cy-1)); }
+                if cy+1 < height { neighbours.push((cx, cy+1)); }
+                for (nx, ny) in neighbours {
+                    if !is_defined(nx, ny) {
+                        touches_undefined = true;
+                    } else if labels[ny][nx].is_none() && is_zero(nx, ny) {
+                        labels[ny][nx] = label;
+                        stack.push((nx, ny));
+                    }
+                }
+            }
+
+            let cut_off = touches_undefined || min_x == 0 || min_y == 0 || max_x+1 == width || max_y+1 == height;
+            let (window_width, window_height) = (max_x-min_x+1, max_y-min_y+1);
+            windows.push(ZeroWindow {
+                top: boundary.top + min_y,
+                left: boundary.left + min_x as isize,
+                size: window_width.max(window_height),
+                width: window_width,
+                height: window_height,
+                cut_off
+            });
+        }
+    }
+
+    (windows, labels)
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum WindowFileFormat {
+    Json,
+    Csv
+}
+
+pub struct ZeroWindowSettings {
+    pub output_file: String,
+    pub format: WindowFileFormat
+}
+
+// Writes every window to the output file and prints how many windows there are of each size on stderr
+pub fn export_zero_windows(windows: &[ZeroWindow], settings: &ZeroWindowSettings) -> Result<(), String> {
+    let text = match settings.format {
+        WindowFileFormat::Json => {
+            let list: Vec<json::JsonValue> = windows.iter().map(|w| json::object!{
+                top: w.top,
+                left: w.left as i64,
+                size: w.size,
+                width: w.width,
+                height: w.height,
+                cut_off: w.cut_off
+            }).collect();
+            json::JsonValue::from(list).pretty(4)
+        },
+        WindowFileFormat::Csv => {
+            let mut csv = "top,left,size,width,height,cut_off\n".to_owned();
+            for w in windows {
+                csv.push_str(&format!("{},{},{},{},{},{}\n", w.top, w.left, w.size, w.width, w.height, w.cut_off));
+            }
+            csv
+        }
+    };
+    fs::write(&settings.output_file, text).map_err(|e| format!("Could not write the zero windows to {}: {e}", settings.output_file))?;
+
+    // the sizes of cut off windows are not known, so they are counted by the width and height that is seen of them
+    let mut histogram: BTreeMap<usize, usize> = BTreeMap::new();
+    let mut cut_off: BTreeMap<(usize, usize), usize> = BTreeMap::new();
+    for w in windows {
+        if w.cut_off {
+            *cut_off.entry((w.width, w.height)).or_insert(0) += 1;
+        } else {
+            *histogram.entry(w.size).or_insert(0) += 1;
+        }
+    }
+    eprintln!("{} zero windows", windows.len());
+    for (size, count) in histogram {
+        eprintln!("size {size}: {count}");
+    }
+    for ((width, height), count) in cut_off {
+        eprintln!("cut off {width} by {height}: {count}");
+    }
+    Ok(())
+}
+
+// Colours the zero cells of a wall by the size of the window they are in,
+// from `low` for single zeros to `high` for windows of size `max` and larger
+#[derive(Debug, Clone, Copy)]
+pub struct WindowSizeColours {
+    pub low: [u8; 3],
+    pub high: [u8; 3],
+    pub max: usize
+}
+
+impl WindowSizeColours {
+    pub fn colour(&self, size: usize) -> [u8; 3] {
+        let size = size.min(self.max).saturating_sub(1) as u32;
+        gradient(self.low, self.high, size, self.max.saturating_sub(1) as u32)
+    }
+}
+
+// The size of the window that each cell is in, from the labels of `label_zero_windows`, `None` for cells outside of every window
+pub fn window_sizes(windows: &[ZeroWindow], labels: &[Vec<Option<usize>>]) -> Vec<Vec<Option<usize>>> {
+    labels.iter().map(|row| row.iter().map(|l| l.map(|i| windows[i].size)).collect()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(rows: &[&str]) -> Vec<Vec<i128>> {
+        rows.iter().map(|r| r.chars().map(|c| i128::from(c != '0')).collect()).collect()
+    }
+
+    const BOUNDARY: Boundary = Boundary { top: 2, bottom: 6, left: -1, right: 4 };
+
+    #[test]
+    fn finds_complete_windows() {
+        let wall = lines(&["111111", "100111", "100101", "111111", "111111"]);
+        let windows = find_zero_windows(&wall, &BOUNDARY);
+        assert_eq!(windows.len(), 2);
+        let (two, one) = (windows[0], windows[1]);
+        assert_eq!((two.top, two.left, two.size, two.width, two.height, two.cut_off), (3, 0, 2, 2, 2, false));
+        assert_eq!((one.top, one.left, one.size, one.width, one.height, one.cut_off), (4, 3, 1, 1, 1, false));
+    }
+
+    #[test]
+    fn windows_at_the_edge_are_cut_off() {
+        let wall = lines(&["000000", "111111", "111111", "111100", "111100"]);
+        let windows = find_zero_windows(&wall, &BOUNDARY);
+        assert_eq!(windows.len(), 2);
+        assert!(windows.iter().all(|w| w.cut_off));
+        assert_eq!((windows[0].width, windows[0].height), (6, 1));
+        assert_eq!((windows[1].top, windows[1].left, windows[1].width, windows[1].height), (5, 3, 2, 2));
+    }
+
+    #[test]
+    fn undefined_cells_cut_windows_off() {
+        let wall = lines(&["111111", "111111", "111011", "111111", "111111"]);
+        let windows = find_zero_windows_masked(&wall, &BOUNDARY, |_, c| c != 1);
+        assert!(windows[0].cut_off);
+        let windows = find_zero_windows_masked(&wall, &BOUNDARY, |r, _| r != 4);
+        assert!(windows.is_empty());
+    }
+
+    #[test]
+    fn row_zero_is_not_a_window() {
+        let boundary = Boundary { top: 0, bottom: 3, left: 0, right: 5 };
+        let wall = lines(&["000000", "111111", "110111", "111111"]);
+        let windows = find_zero_windows(&wall, &boundary);
+        assert_eq!(windows.len(), 1);
+        assert_eq!((windows[0].top, windows[0].left, windows[0].cut_off), (2, 2, false));
+    }
+
+    #[test]
+    fn labels_are_exactly_the_cells_of_each_window() {
+        let wall = lines(&["111111", "111111", "110111", "100011", "100011"]);
+        let (windows, labels) = label_zero_windows(&wall, &BOUNDARY, |_, _| true);
+        assert_eq!(windows.len(), 1);
+        let labelled: Vec<(usize, usize)> = (0..5).flat_map(|y| (0..6).map(move |x| (y, x)))
+            .filter(|&(y, x)| labels[y][x] == Some(0))
+            .collect();
+        assert_eq!(labelled, vec![(2, 2), (3, 1), (3, 2), (3, 3), (4, 1), (4, 2), (4, 3)]);
+        let sizes = window_sizes(&windows, &labels);
+        assert_eq!(sizes[2][1], None);
+        assert_eq!(sizes[4][3], Some(3));
+    }
+
+    #[test]
+    fn window_size_colours() {
+        let colours = WindowSizeColours { low: [255, 255, 255], high: [0, 0, 255], max: 5 };
+        assert_eq!(colours.colour(1), [255, 255, 255]);
+        assert_eq!(colours.colour(3), [128, 128, 255]);
+        assert_eq!(colours.colour(5), [0, 0, 255]);
+        assert_eq!(colours.colour(40), [0, 0, 255]);
+    }
+}